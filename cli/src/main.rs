@@ -1,63 +1,415 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
 use anyhow::Result;
-use clap::Parser;
-use sanitiser::pdf::sanitise::regenerate_pdf;
+use clap::{Args as ClapArgs, Parser, Subcommand};
+use sanitiser::pdf::merge::merge_pdf_files;
+use sanitiser::pdf::sanitise::regenerate_pdf_from_bytes;
+use sanitiser::source::{ObjectStorageConfig, Sink, Source};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 use tracing_subscriber::EnvFilter;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 
-use std::path::{Path, PathBuf};
-use std::time::Instant;
-
 #[derive(Parser)]
 #[command(name = "sanitisium-cli")]
-#[command(about = "Tool for regenerating PDFs")]
+#[command(about = "Tool for regenerating and merging PDFs")]
 #[command(version)]
-struct Args {
-    /// Path to the input PDF file to sanitise
-    #[arg(help = "The PDF file to sanitise")]
-    input: PathBuf,
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Sanitise one or more PDFs (historically the only behavior)
+    Sanitise(SanitiseArgs),
+    /// Merge multiple PDFs into a single output file
+    Merge(MergeArgs),
+}
+
+#[derive(ClapArgs)]
+struct SanitiseArgs {
+    /// PDF file(s) to sanitise. A local directory is expanded
+    /// (non-recursively) to every `*.pdf` file it contains; an `s3://bucket/key`
+    /// URI is read straight from the bucket instead of local disk.
+    #[arg(
+        required = true,
+        help = "PDF file(s), directories of PDFs, or s3://bucket/key URIs to sanitise"
+    )]
+    inputs: Vec<String>,
 
-    /// Path to the output PDF file (optional)
+    /// Output path (or `s3://bucket/key` URI) for the sanitised PDF. Only
+    /// applies when exactly one input file is resolved; in batch mode every
+    /// output is written next to its input, prefixed with `regenerated_`,
+    /// and this is ignored.
     #[arg(
         short,
         long,
-        help = "Output path for the sanitised PDF. Defaults to the input filename prefixed with 'regenerated_'"
+        help = "Output path (or s3://bucket/key URI) for the sanitised PDF. Defaults to the input filename prefixed with 'regenerated_'"
+    )]
+    output: Option<String>,
+
+    /// Maximum number of files sanitised concurrently in batch mode.
+    #[arg(
+        short = 'j',
+        long,
+        default_value_t = 4,
+        help = "Maximum number of files sanitised at the same time"
+    )]
+    jobs: usize,
+
+    /// Number of attempts per file (including the first) before giving up
+    /// on it and recording it as a failure.
+    #[arg(
+        long,
+        default_value_t = 3,
+        help = "Attempts per file before giving up on it"
+    )]
+    retries: u32,
+
+    #[command(flatten)]
+    object_storage: ObjectStorageArgs,
+}
+
+#[derive(ClapArgs)]
+struct MergeArgs {
+    /// PDF files to merge, in order. The first becomes the base document
+    /// that the rest are appended to.
+    #[arg(required = true, num_args = 2.., help = "PDF files to merge, in order")]
+    inputs: Vec<PathBuf>,
+
+    /// Path to write the merged PDF to.
+    #[arg(short, long, help = "Output path for the merged PDF")]
+    output: PathBuf,
+
+    /// Title set on the merged document's `/Info` dictionary. Setting it also
+    /// adds a bookmark outline with one "Document N" entry per input file.
+    #[arg(long, help = "Title for the merged document's bookmark/metadata")]
+    title: Option<String>,
+
+    /// Sanitise each input (via `regenerate_pdf`) before merging, so the
+    /// merged output can't carry over active content from the originals.
+    #[arg(
+        long,
+        help = "Sanitise each input with regenerate_pdf before merging"
+    )]
+    sanitise: bool,
+}
+
+/// Region/endpoint for the S3 client used to resolve any `s3://` input or
+/// output URI. Shared across subcommands that accept them.
+#[derive(ClapArgs)]
+struct ObjectStorageArgs {
+    #[arg(long, help = "AWS region for s3:// inputs/outputs")]
+    s3_region: Option<String>,
+
+    #[arg(
+        long,
+        help = "Endpoint override for S3-compatible stores (MinIO, R2, ...)"
     )]
-    output: Option<PathBuf>,
+    s3_endpoint: Option<String>,
 }
 
-fn main() -> Result<()> {
+impl From<ObjectStorageArgs> for ObjectStorageConfig {
+    fn from(args: ObjectStorageArgs) -> Self {
+        ObjectStorageConfig {
+            region: args.s3_region,
+            endpoint: args.s3_endpoint,
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
     init_tracing()?;
-    let args = Args::parse();
-
-    let output_path = match args.output {
-        Some(path) => path,
-        None => {
-            let input_path = &args.input;
-            let parent_dir = input_path.parent().unwrap_or(Path::new("."));
-            let file_stem = input_path
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Sanitise(args) => run_sanitise(args).await,
+        Command::Merge(args) => run_merge(args).await,
+    }
+}
+
+async fn run_sanitise(args: SanitiseArgs) -> Result<()> {
+    let storage: ObjectStorageConfig = args.object_storage.into();
+    let inputs = expand_inputs(&args.inputs)?;
+    if inputs.is_empty() {
+        anyhow::bail!("No PDF files found among the given inputs");
+    }
+
+    if inputs.len() == 1 {
+        let input = inputs.into_iter().next().unwrap();
+        let sink = match args.output {
+            Some(raw) => Sink::parse(&raw),
+            None => default_sink_for(&input),
+        };
+        let start_time = Instant::now();
+        regenerate_with_retries(&input, &sink, &storage, args.retries).await?;
+        tracing::info!(
+            "Regenerated PDF saved to {} in {:?}",
+            describe_sink(&sink),
+            start_time.elapsed()
+        );
+        return Ok(());
+    }
+
+    if args.output.is_some() {
+        tracing::warn!("--output is ignored in batch mode; each file is written next to its input");
+    }
+
+    // Bounded worker pool: every spawned task must acquire a permit before
+    // calling into pdfium, so at most `jobs` files regenerate at once
+    // regardless of how many were passed in.
+    let semaphore = Arc::new(Semaphore::new(args.jobs.max(1)));
+    let mut tasks = JoinSet::new();
+
+    for input in inputs {
+        let semaphore = Arc::clone(&semaphore);
+        let retries = args.retries;
+        let storage = storage.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await.expect("semaphore closed");
+            let sink = default_sink_for(&input);
+            let result = regenerate_with_retries(&input, &sink, &storage, retries).await;
+            (input, sink, result)
+        });
+    }
+
+    let mut successes = 0usize;
+    let mut failures = Vec::new();
+
+    while let Some(result) = tasks.join_next().await {
+        let (input, sink, result) = result.expect("sanitisation task panicked");
+        match result {
+            Ok(()) => {
+                tracing::info!(
+                    "Regenerated {} -> {}",
+                    describe_source(&input),
+                    describe_sink(&sink)
+                );
+                successes += 1;
+            }
+            Err(error) => {
+                tracing::error!("Failed to sanitise {}. error={error}", describe_source(&input));
+                failures.push((input, error));
+            }
+        }
+    }
+
+    println!("\n{successes} succeeded, {} failed", failures.len());
+    for (input, error) in &failures {
+        println!("  FAILED {}: {error}", describe_source(input));
+    }
+
+    if !failures.is_empty() {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Merges `args.inputs` (optionally sanitising each one first) into a single
+/// PDF written to `args.output`. Local files only — `merge_pdf_files` reads
+/// directly off disk, unlike the `Source`/`Sink`-based single-file path.
+async fn run_merge(args: MergeArgs) -> Result<()> {
+    let inputs = expand_local_inputs(&args.inputs)?;
+    if inputs.len() < 2 {
+        anyhow::bail!("merge requires at least two PDF files");
+    }
+
+    let start_time = Instant::now();
+
+    // When requested, sanitise every input into a scratch file first so the
+    // merged output can't carry over active content from the originals;
+    // otherwise merge the inputs as given.
+    let staged_inputs = if args.sanitise {
+        let mut staged = Vec::with_capacity(inputs.len());
+        for input in &inputs {
+            let scratch = std::env::temp_dir().join(format!(
+                "sanitisium-merge-{}-{}",
+                std::process::id(),
+                input
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .unwrap_or("input.pdf")
+            ));
+            regenerate_with_retries(
+                &Source::Local(input.clone()),
+                &Sink::Local(scratch.clone()),
+                &ObjectStorageConfig::default(),
+                1,
+            )
+            .await?;
+            staged.push(scratch);
+        }
+        staged
+    } else {
+        inputs
+    };
+
+    merge_pdf_files(&staged_inputs, &args.output, args.title.as_deref(), None)?;
+
+    if args.sanitise {
+        for staged in &staged_inputs {
+            let _ = std::fs::remove_file(staged);
+        }
+    }
+
+    tracing::info!(
+        "Merged {} files into {} in {:?}",
+        staged_inputs.len(),
+        args.output.display(),
+        start_time.elapsed()
+    );
+
+    Ok(())
+}
+
+/// Resolves the given paths to a flat list of PDF files, expanding any
+/// directory (non-recursively) to the `*.pdf` files directly inside it.
+fn expand_local_inputs(inputs: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut resolved = Vec::new();
+    for input in inputs {
+        if input.is_dir() {
+            for entry in std::fs::read_dir(input)? {
+                let path = entry?.path();
+                if path.extension().and_then(|ext| ext.to_str()) == Some("pdf") {
+                    resolved.push(path);
+                }
+            }
+        } else {
+            resolved.push(input.clone());
+        }
+    }
+    Ok(resolved)
+}
+
+/// Resolves the given strings to a flat list of [`Source`]s: an `s3://`
+/// URI is passed through as-is, a local directory is expanded
+/// (non-recursively) to the `*.pdf` files directly inside it, and anything
+/// else is treated as a single local file.
+fn expand_inputs(inputs: &[String]) -> Result<Vec<Source>> {
+    let mut resolved = Vec::new();
+    for raw in inputs {
+        if raw.starts_with("s3://") {
+            resolved.push(Source::parse(raw));
+            continue;
+        }
+
+        let path = PathBuf::from(raw);
+        if path.is_dir() {
+            for entry in std::fs::read_dir(&path)? {
+                let entry_path = entry?.path();
+                if entry_path.extension().and_then(|ext| ext.to_str()) == Some("pdf") {
+                    resolved.push(Source::Local(entry_path));
+                }
+            }
+        } else {
+            resolved.push(Source::Local(path));
+        }
+    }
+    Ok(resolved)
+}
+
+fn default_output_path(input_path: &Path) -> PathBuf {
+    let parent_dir = input_path.parent().unwrap_or(Path::new("."));
+    let file_stem = input_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("sanitised");
+    let extension = input_path
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("pdf");
+
+    parent_dir.join(format!("regenerated_{file_stem}.{extension}"))
+}
+
+/// Default output location for a given input: alongside it, prefixed with
+/// `regenerated_`, in the same bucket for an S3 source or the same
+/// directory for a local one.
+fn default_sink_for(source: &Source) -> Sink {
+    match source {
+        Source::Local(path) => Sink::Local(default_output_path(path)),
+        Source::S3 { bucket, key } => {
+            let key_path = Path::new(key);
+            let parent = key_path.parent().filter(|p| !p.as_os_str().is_empty());
+            let file_stem = key_path
                 .file_stem()
                 .and_then(|s| s.to_str())
                 .unwrap_or("sanitised");
-            let extension = input_path
+            let extension = key_path
                 .extension()
                 .and_then(|s| s.to_str())
                 .unwrap_or("pdf");
-
-            parent_dir.join(format!("regenerated_{file_stem}.{extension}"))
+            let new_name = format!("regenerated_{file_stem}.{extension}");
+            let new_key = match parent {
+                Some(parent) => format!("{}/{new_name}", parent.display()),
+                None => new_name,
+            };
+            Sink::S3 {
+                bucket: bucket.clone(),
+                key: new_key,
+            }
         }
-    };
+    }
+}
 
-    let start_time = Instant::now();
-    regenerate_pdf(&args.input, &output_path)?;
+fn describe_source(source: &Source) -> String {
+    match source {
+        Source::Local(path) => path.display().to_string(),
+        Source::S3 { bucket, key } => format!("s3://{bucket}/{key}"),
+    }
+}
 
-    let duration = start_time.elapsed();
-    tracing::info!(
-        "Regenerated PDF saved to {} in {:?}",
-        output_path.display(),
-        duration
-    );
+fn describe_sink(sink: &Sink) -> String {
+    match sink {
+        Sink::Local(path) => path.display().to_string(),
+        Sink::S3 { bucket, key } => format!("s3://{bucket}/{key}"),
+    }
+}
+
+/// Reads `source`, runs `regenerate_pdf_from_bytes` on a blocking thread,
+/// and writes the result to `sink`, retrying up to `max_attempts` times with
+/// exponential backoff (200ms, 400ms, 800ms, ...) before giving up, so one
+/// malformed file doesn't abort the whole run.
+async fn regenerate_with_retries(
+    source: &Source,
+    sink: &Sink,
+    storage: &ObjectStorageConfig,
+    max_attempts: u32,
+) -> Result<()> {
+    let max_attempts = max_attempts.max(1);
+    let mut attempt = 0;
+
+    loop {
+        attempt += 1;
+        let result = regenerate_once(source, sink, storage).await;
+
+        match result {
+            Ok(()) => return Ok(()),
+            Err(error) if attempt < max_attempts => {
+                let backoff = Duration::from_millis(200 * 2u64.pow(attempt - 1));
+                tracing::warn!(
+                    "Attempt {attempt}/{max_attempts} failed for {}. error={error} retrying_in={backoff:?}",
+                    describe_source(source)
+                );
+                tokio::time::sleep(backoff).await;
+            }
+            Err(error) => return Err(error),
+        }
+    }
+}
 
+async fn regenerate_once(source: &Source, sink: &Sink, storage: &ObjectStorageConfig) -> Result<()> {
+    let input_bytes = source.read(storage).await?;
+    let output_bytes = tokio::task::spawn_blocking(move || regenerate_pdf_from_bytes(&input_bytes))
+        .await
+        .expect("regenerate_pdf_from_bytes task panicked")?;
+    sink.write(&output_bytes, storage).await?;
     Ok(())
 }
 
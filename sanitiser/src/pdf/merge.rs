@@ -1,33 +1,89 @@
 use anyhow::Error;
-use lopdf::{Document, Object};
+use lopdf::{Dictionary, Document, Object, ObjectId};
 use std::collections::BTreeMap;
 use std::fs::File;
 use std::path::Path;
-use std::time::Instant;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// lopdf's merge errors are just `anyhow::Error` under the hood; this alias
+/// exists so callers (e.g. `PDFRegenerationError::BadMerge`) don't need to
+/// depend on `anyhow` directly.
+pub type MergePDFError = Error;
 
 /// Merge every file in `inputs` into a single PDF file at `output_path`.
 /// The first file becomes the "base"; all others are appended.
 ///
-/// Implementation inspired on the reference example from the [lopdf repo here.](https://github.com/J-F-Liu/lopdf/blob/c320c1d9d90028ee64e668f0bbbe9815fae3fb44/examples/merge.rs)
-pub fn merge_pdf_files<P>(files: &[P], output_path: &P) -> Result<(), Error>
+/// `title` sets the merged document's `/Info` title, and `labels`, when
+/// provided, gives each input file's outline entry a friendly name instead
+/// of "Document N". Passing either one adds a bookmark outline with one
+/// top-level entry per input file. Both are optional — pass `None` for a
+/// title-less, bookmark-less merge.
+pub fn merge_pdf_files<P>(
+    files: &[P],
+    output_path: &P,
+    title: Option<&str>,
+    labels: Option<&[String]>,
+) -> Result<(), Error>
 where
     P: AsRef<Path>,
 {
-    if files.is_empty() {
-        return Err(anyhow::anyhow!("No input files provided"));
-    }
+    let start_time = Instant::now();
+
+    let docs = files
+        .iter()
+        .map(|path| Document::load_from(File::open(path.as_ref())?).map_err(Error::from))
+        .collect::<Result<Vec<_>, _>>()?;
 
+    let mut merged_doc = merge_documents(docs, title, labels)?;
+    merged_doc.save(output_path.as_ref())?;
+    println!("Time taken to merge final PDF: {:?}", start_time.elapsed());
+    Ok(())
+}
+
+/// Same as [`merge_pdf_files`], but for callers holding already-decoded PDF
+/// bytes in memory (e.g. chunks rasterised by `regenerate_pdf_from_bytes`)
+/// instead of files on disk — avoids a round trip through temp files just to
+/// hand them back to lopdf.
+pub fn merge_pdf_bytes(
+    buffers: &[Vec<u8>],
+    title: Option<&str>,
+    labels: Option<&[String]>,
+) -> Result<Vec<u8>, Error> {
     let start_time = Instant::now();
 
+    let docs = buffers
+        .iter()
+        .map(|bytes| Document::load_mem(bytes).map_err(Error::from))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut merged_doc = merge_documents(docs, title, labels)?;
+    let mut output = Vec::new();
+    merged_doc.save_to(&mut output)?;
+    println!("Time taken to merge final PDF: {:?}", start_time.elapsed());
+    Ok(output)
+}
+
+/// Shared core of [`merge_pdf_files`] and [`merge_pdf_bytes`]: merge already
+/// loaded `docs`, in order, into a single [`Document`] without saving it
+/// anywhere. The first document becomes the "base"; all others are appended.
+///
+/// Implementation inspired on the reference example from the [lopdf repo here.](https://github.com/J-F-Liu/lopdf/blob/c320c1d9d90028ee64e668f0bbbe9815fae3fb44/examples/merge.rs)
+fn merge_documents(
+    mut docs: Vec<Document>,
+    title: Option<&str>,
+    labels: Option<&[String]>,
+) -> Result<Document, Error> {
+    if docs.is_empty() {
+        return Err(anyhow::anyhow!("No input files provided"));
+    }
+
     // Start with the first document as the base
-    let first_path = &files[0];
-    let first_file = File::open(first_path.as_ref())?;
-    let mut merged_doc = Document::load_from(first_file)?;
+    let mut merged_doc = docs.remove(0);
 
-    if files.len() == 1 {
-        // Only one file, just save it to the given output and bail
-        merged_doc.save(output_path.as_ref())?;
-        return Ok(());
+    if docs.is_empty() {
+        // Only one document, nothing to append
+        set_document_info(&mut merged_doc, title);
+        return Ok(merged_doc);
     }
 
     // Track the next available object ID
@@ -36,22 +92,29 @@ where
     let mut all_pages = BTreeMap::new();
     let mut all_objects = BTreeMap::new();
 
+    // Object ID of the first page contributed by each source document, in
+    // source order, so we can later point a bookmark outline item at it.
+    let mut first_page_per_source: Vec<ObjectId> = Vec::with_capacity(docs.len() + 1);
+
     // Add all pages from the base document
     // to our accumulator
     let base_pages = merged_doc.get_pages();
+    if let Some((_, &first_page_id)) = base_pages.iter().min_by_key(|(page_num, _)| **page_num) {
+        first_page_per_source.push(first_page_id);
+    }
     for (_, page_id) in base_pages {
         all_pages.insert(page_id, merged_doc.get_object(page_id)?.clone());
     }
 
-    for input_path in files.iter().skip(1) {
-        let file = File::open(input_path.as_ref())?;
-        let mut doc = Document::load_from(file)?;
-
+    for mut doc in docs {
         // Renumber objects to avoid conflicts
         doc.renumber_objects_with(max_id);
         max_id = doc.max_id + 1;
 
         let pages = doc.get_pages();
+        if let Some((_, &first_page_id)) = pages.iter().min_by_key(|(page_num, _)| **page_num) {
+            first_page_per_source.push(first_page_id);
+        }
 
         // Now we should get all pages from each document
         // and add it to our final container collection
@@ -114,14 +177,158 @@ where
         }
     }
 
+    set_document_info(&mut merged_doc, title);
+    // Only build an outline when the caller passes `title` and/or `labels`.
+    // `process_raster_job` and `regenerate_pdf_from_bytes` merge rasterised
+    // page-chunks back into one PDF with neither, so they get no bookmarks;
+    // for file-based input `regenerate_bytes` passes the input's name as
+    // `title`, so that output gets one "Document N" entry per page batch.
+    if title.is_some() || labels.is_some() {
+        add_bookmark_outline(&mut merged_doc, &first_page_per_source, labels)?;
+    }
+
     // Update max_id and renumber objects to ensure consistency
     // before saving the final merged document
     merged_doc.max_id = merged_doc.objects.len() as u32;
     merged_doc.renumber_objects();
 
-    // Save the merged document
-    merged_doc.save(output_path.as_ref())?;
-    println!("Time taken to merge final PDF: {:?}", start_time.elapsed());
+    Ok(merged_doc)
+}
+
+/// Set `/Title`, `/Producer` and `/CreationDate` on the document's `/Info`
+/// dictionary. `title` is optional — when absent, only `/Producer` and
+/// `/CreationDate` are stamped.
+fn set_document_info(doc: &mut Document, title: Option<&str>) {
+    let info_id = match doc.trailer.get(b"Info").and_then(|info| info.as_reference()) {
+        Ok(id) => id,
+        Err(_) => {
+            let id = doc.add_object(Object::Dictionary(Dictionary::new()));
+            doc.trailer.set("Info", Object::Reference(id));
+            id
+        }
+    };
+
+    if let Ok(info_obj) = doc.get_object_mut(info_id)
+        && let Ok(info_dict) = info_obj.as_dict_mut()
+    {
+        if let Some(title) = title {
+            info_dict.set("Title", Object::string_literal(title));
+        }
+        info_dict.set("Producer", Object::string_literal("sanitisium"));
+        info_dict.set("CreationDate", Object::string_literal(pdf_date_now()));
+    }
+}
+
+/// Produce a PDF-format date string (`D:YYYYMMDDHHmmSSZ`) from the current
+/// UTC time, without pulling in a date/time dependency just for this.
+fn pdf_date_now() -> String {
+    let secs_since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    // Naive Gregorian calendar conversion from a Unix timestamp.
+    let days_since_epoch = secs_since_epoch / 86_400;
+    let secs_of_day = secs_since_epoch % 86_400;
+    let (hour, minute, second) = (
+        secs_of_day / 3600,
+        (secs_of_day % 3600) / 60,
+        secs_of_day % 60,
+    );
+
+    let mut remaining_days = days_since_epoch as i64;
+    let mut year = 1970i64;
+    loop {
+        let days_in_year = if is_leap_year(year) { 366 } else { 365 };
+        if remaining_days < days_in_year {
+            break;
+        }
+        remaining_days -= days_in_year;
+        year += 1;
+    }
+
+    let month_lengths = [31, if is_leap_year(year) { 29 } else { 28 }, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+    let mut month = 1;
+    for length in month_lengths {
+        if remaining_days < length {
+            break;
+        }
+        remaining_days -= length;
+        month += 1;
+    }
+    let day = remaining_days + 1;
+
+    format!("D:{year:04}{month:02}{day:02}{hour:02}{minute:02}{second:02}Z")
+}
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+/// Build an `/Outlines` bookmark tree with one top-level item per source
+/// file, each pointing at that file's first (renumbered) page. No-op when
+/// there are no source pages to bookmark.
+fn add_bookmark_outline(
+    doc: &mut Document,
+    first_page_per_source: &[ObjectId],
+    labels: Option<&[String]>,
+) -> Result<(), Error> {
+    if first_page_per_source.is_empty() {
+        return Ok(());
+    }
+
+    let outlines_id = doc.add_object(Object::Dictionary(Dictionary::new()));
+    let mut item_ids = Vec::with_capacity(first_page_per_source.len());
+
+    for (index, &page_id) in first_page_per_source.iter().enumerate() {
+        let title = labels
+            .and_then(|labels| labels.get(index))
+            .cloned()
+            .unwrap_or_else(|| format!("Document {}", index + 1));
+
+        let mut item = Dictionary::new();
+        item.set("Title", Object::string_literal(title));
+        item.set("Parent", Object::Reference(outlines_id));
+        item.set(
+            "Dest",
+            Object::Array(vec![
+                Object::Reference(page_id),
+                "Fit".into(),
+            ]),
+        );
+        item_ids.push(doc.add_object(Object::Dictionary(item)));
+    }
+
+    // Chain the items together via /First, /Last, /Next, /Prev
+    for (index, &item_id) in item_ids.iter().enumerate() {
+        let mut item = doc
+            .get_object(item_id)?
+            .as_dict()
+            .map_err(|_| anyhow::anyhow!("Outline item is not a dictionary"))?
+            .clone();
+
+        if index > 0 {
+            item.set("Prev", Object::Reference(item_ids[index - 1]));
+        }
+        if index + 1 < item_ids.len() {
+            item.set("Next", Object::Reference(item_ids[index + 1]));
+        }
+
+        doc.objects.insert(item_id, Object::Dictionary(item));
+    }
+
+    let mut outlines_dict = Dictionary::new();
+    outlines_dict.set("Type", Object::Name(b"Outlines".to_vec()));
+    outlines_dict.set("First", Object::Reference(item_ids[0]));
+    outlines_dict.set("Last", Object::Reference(*item_ids.last().unwrap()));
+    outlines_dict.set("Count", item_ids.len() as i64);
+    doc.objects
+        .insert(outlines_id, Object::Dictionary(outlines_dict));
+
+    if let Ok(catalog) = doc.catalog_mut() {
+        catalog.set("Outlines", Object::Reference(outlines_id));
+    }
+
     Ok(())
 }
 
@@ -149,7 +356,7 @@ mod tests {
         let output_file = NamedTempFile::new().expect("Failed to create temp file");
         let output_path = output_file.path().to_path_buf();
 
-        let result = merge_pdf_files(&[input], &output_path);
+        let result = merge_pdf_files(&[input], &output_path, None, None);
         assert!(result.is_ok(), "Failed to merge single PDF: {:?}", result);
 
         // Verify the output file exists and is a valid PDF
@@ -166,7 +373,7 @@ mod tests {
         let output_file = NamedTempFile::new().expect("Failed to create temp file");
         let output_path = output_file.path().to_path_buf();
 
-        let result = merge_pdf_files(&[input1.clone(), input2.clone()], &output_path);
+        let result = merge_pdf_files(&[input1.clone(), input2.clone()], &output_path, None, None);
         assert!(result.is_ok(), "Failed to merge two PDFs: {:?}", result);
 
         // Verify the output file exists and is a valid PDF
@@ -205,6 +412,8 @@ mod tests {
         let result = merge_pdf_files(
             &[input1.clone(), input2.clone(), input3.clone()],
             &output_path,
+            None,
+            None,
         );
         assert!(
             result.is_ok(),
@@ -245,7 +454,7 @@ mod tests {
         let output_file = NamedTempFile::new().expect("Failed to create temp file");
         let output_path = output_file.path().to_path_buf();
 
-        let result = merge_pdf_files::<PathBuf>(&[], &output_path);
+        let result = merge_pdf_files::<PathBuf>(&[], &output_path, None, None);
         assert!(result.is_err(), "Should return error for empty input list");
 
         let error_message = result.unwrap_err().to_string();
@@ -262,7 +471,7 @@ mod tests {
         let output_file = NamedTempFile::new().expect("Failed to create temp file");
         let output_path = output_file.path().to_path_buf();
 
-        let result = merge_pdf_files(&[nonexistent], &output_path);
+        let result = merge_pdf_files(&[nonexistent], &output_path, None, None);
         assert!(result.is_err(), "Should return error for nonexistent file");
     }
 
@@ -271,7 +480,7 @@ mod tests {
         let input = get_test_pdf_path("page-sizes-test.pdf");
         let invalid_output = PathBuf::from("/invalid/directory/that/does/not/exist/output.pdf");
 
-        let result = merge_pdf_files(&[input], &invalid_output);
+        let result = merge_pdf_files(&[input], &invalid_output, None, None);
         assert!(
             result.is_err(),
             "Should return error for invalid output path"
@@ -286,7 +495,8 @@ mod tests {
         let output_file = NamedTempFile::new().expect("Failed to create temp file");
         let output_path = output_file.path().to_path_buf();
 
-        merge_pdf_files(&[input1, input2], &output_path.clone()).expect("Failed to merge PDFs");
+        merge_pdf_files(&[input1, input2], &output_path.clone(), None, None)
+            .expect("Failed to merge PDFs");
 
         let output_file = File::open(&output_path).expect("Failed to open output file");
         let merged_doc = Document::load_from(output_file).expect("Failed to load merged PDF");
@@ -332,4 +542,60 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_merge_bytes_two_files() {
+        let input1 = std::fs::read(get_test_pdf_path("page-sizes-test.pdf")).unwrap();
+        let input2 = std::fs::read(get_test_pdf_path("annotations-test.pdf")).unwrap();
+
+        let result = merge_pdf_bytes(&[input1, input2], None, None);
+        assert!(result.is_ok(), "Failed to merge two PDFs: {:?}", result);
+
+        let merged_doc = Document::load_mem(&result.unwrap());
+        assert!(merged_doc.is_ok(), "Output is not a valid PDF");
+    }
+
+    #[test]
+    fn test_merge_bytes_matches_merge_files() {
+        let input1 = get_test_pdf_path("page-sizes-test.pdf");
+        let input2 = get_test_pdf_path("export-test.pdf");
+        let output_file = NamedTempFile::new().expect("Failed to create temp file");
+        let output_path = output_file.path().to_path_buf();
+
+        merge_pdf_files(&[input1.clone(), input2.clone()], &output_path, None, None)
+            .expect("Failed to merge via files");
+
+        let file_result =
+            std::fs::read(&output_path).expect("Failed to read file-based merge output");
+        let file_doc =
+            Document::load_mem(&file_result).expect("File-based merge output is not valid");
+
+        let bytes_result = merge_pdf_bytes(
+            &[std::fs::read(&input1).unwrap(), std::fs::read(&input2).unwrap()],
+            None,
+            None,
+        )
+        .expect("Failed to merge via bytes");
+        let bytes_doc =
+            Document::load_mem(&bytes_result).expect("Bytes-based merge output is not valid");
+
+        assert_eq!(
+            file_doc.get_pages().len(),
+            bytes_doc.get_pages().len(),
+            "Both merge entry points should produce the same page count"
+        );
+    }
+
+    #[test]
+    fn test_merge_bytes_empty_input_list() {
+        let result = merge_pdf_bytes(&[], None, None);
+        assert!(result.is_err(), "Should return error for empty input list");
+
+        let error_message = result.unwrap_err().to_string();
+        assert!(
+            error_message.contains("No input files provided"),
+            "Error message should mention no input files, got: {}",
+            error_message
+        );
+    }
 }
@@ -0,0 +1,113 @@
+#![cfg(feature = "ocr")]
+
+//! Optional OCR text layer for rasterised pages, gated behind the `ocr`
+//! cargo feature since it pulls in a Tesseract binding purely for this one
+//! opt-in step (see [`crate::pdf::sanitise::RegenerationOptions::ocr`]).
+//! `regenerate_pdf` otherwise produces pages with zero selectable text —
+//! every glyph was flattened into the page's JPEG/PNG/WebP image.
+
+use leptess::LepTess;
+use printpdf::{BuiltinFont, FontId, Mm, Op, Point, Pt, TextItem, TextRenderingMode};
+use thiserror::Error;
+
+/// A single OCR'd word and its pixel-space bounding box within the bitmap
+/// it was recognised from. Pixel origin is top-left, matching the bitmap
+/// `regenerate_bytes` rasterised the page into.
+struct OcrWord {
+    text: String,
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+#[derive(Error, Debug)]
+pub enum OcrError {
+    #[error("OCR engine failed to initialise: {0}")]
+    EngineInit(String),
+    #[error("OCR engine failed to load page image: {0}")]
+    ImageLoad(String),
+}
+
+fn ocr_words(image_bytes: &[u8]) -> Result<Vec<OcrWord>, OcrError> {
+    let mut engine = LepTess::new(None, "eng").map_err(|e| OcrError::EngineInit(e.to_string()))?;
+    engine
+        .set_image_from_mem(image_bytes)
+        .map_err(|e| OcrError::ImageLoad(e.to_string()))?;
+
+    Ok(engine
+        .get_word_boxes()
+        .into_iter()
+        .map(|(text, bbox)| OcrWord {
+            text,
+            x: bbox.x,
+            y: bbox.y,
+            width: bbox.w,
+            height: bbox.h,
+        })
+        .collect())
+}
+
+/// Run OCR over `image_bytes` (the same encoded page bitmap already placed
+/// on the page as an `/XObject`) and return an invisible (render mode 3)
+/// text layer reproducing every recognised word at its original position,
+/// so the rasterised page stays searchable/selectable without being
+/// visibly altered.
+///
+/// Each OCR'd pixel box is mapped back to page millimetres using
+/// `25.4 / dpi` (pixels to inches to mm), which undoes the `dpi / 72.0`
+/// scale `regenerate_bytes` used to go from PDF points to render pixels in
+/// the first place — then flipped from the bitmap's top-left pixel origin
+/// to the page's bottom-left origin via `page_height_mm`.
+pub fn ocr_text_layer_ops(
+    image_bytes: &[u8],
+    dpi: f32,
+    page_height_mm: f32,
+    font: FontId,
+) -> Result<Vec<Op>, OcrError> {
+    let words = ocr_words(image_bytes)?;
+    let px_to_mm = 25.4 / dpi;
+
+    let mut ops = Vec::with_capacity(words.len() * 3 + 2);
+    ops.push(Op::StartTextSection);
+    ops.push(Op::SetTextRenderingMode {
+        mode: TextRenderingMode::Invisible,
+    });
+
+    for word in words {
+        if word.text.trim().is_empty() {
+            continue;
+        }
+
+        let x_mm = word.x as f32 * px_to_mm;
+        // Anchor at the bottom edge of the word's box, flipped into
+        // bottom-left page space.
+        let y_mm = page_height_mm - (word.y + word.height) as f32 * px_to_mm;
+        let font_size_pt = word.height as f32 * px_to_mm * (72.0 / 25.4);
+
+        ops.push(Op::SetFontSize {
+            size: Pt(font_size_pt),
+            font: font.clone(),
+        });
+        ops.push(Op::SetTextCursor {
+            pos: Point {
+                x: Mm(x_mm).into_pt(),
+                y: Mm(y_mm).into_pt(),
+            },
+        });
+        ops.push(Op::WriteText {
+            items: vec![TextItem::Text(word.text)],
+            font: font.clone(),
+        });
+    }
+
+    ops.push(Op::EndTextSection);
+    Ok(ops)
+}
+
+/// Add a builtin Helvetica font to `doc_out` for use with
+/// [`ocr_text_layer_ops`]. Helvetica has no embedded font file, keeping the
+/// invisible text layer's overhead small.
+pub fn add_ocr_font(doc_out: &mut printpdf::PdfDocument) -> FontId {
+    doc_out.add_builtin_font(BuiltinFont::Helvetica)
+}
@@ -5,19 +5,151 @@ use printpdf::{
     XObjectTransform,
 };
 use std::cmp::min;
-use std::fs::File;
-use std::io::{self, Cursor, Write};
-use std::path::{Path, PathBuf};
-use std::{env, fs};
+use std::fs;
+use std::io::{self, Cursor, Read, Write};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
 use thiserror::Error;
-use uuid::Uuid;
 
-use crate::pdf::merge::{MergePDFError, merge_pdf_files};
+use crate::pdf::load_pdfium::get_pdfium_instance;
+use crate::pdf::merge::{MergePDFError, merge_pdf_bytes};
+#[cfg(feature = "ocr")]
+use crate::pdf::ocr;
+use crate::pdf::scan::{
+    ScanError, ThreatCategory, ThreatReport, scan_pdf_bytes, scan_pdf_bytes_with_blacklist,
+};
+use crate::pdf::structural::{StructuralCleanError, structural_clean};
 
 const PAGE_BATCH: u16 = 5;
 const JPG_QUALITY: f32 = 70f32;
 const DPI: f32 = 300.0;
 
+/// Process-wide `Pdfium` instance, bound to the shared library exactly once.
+/// Binding (locating and loading the dynamic library) is the expensive part
+/// of `get_pdfium_instance`, so a long-running process sanitising many files
+/// reuses this instead of re-binding on every `regenerate_pdf` call.
+///
+/// Pdfium's C++ internals aren't reentrant, so access is serialised behind a
+/// `Mutex`: concurrent `regenerate_pdf` calls queue for the instance itself.
+/// The lock is held for the whole call rather than just the render step —
+/// `PdfDocument`/`PdfPage` borrow from it for as long as the input stays
+/// loaded, so there's no way to release it mid-call and let the
+/// JPEG-encode/printpdf-assembly work overlap without restructuring this
+/// function around short-lived per-page documents.
+static SHARED_PDFIUM: OnceLock<Mutex<Pdfium>> = OnceLock::new();
+
+fn shared_pdfium() -> &'static Mutex<Pdfium> {
+    SHARED_PDFIUM.get_or_init(|| Mutex::new(get_pdfium_instance()))
+}
+
+/// Output image codec used both for the intermediate page rasterisation and
+/// for printpdf's own re-compression of the embedded images. WebP and
+/// greyscale (see [`RegenerationOptions::greyscale`]) are the two biggest
+/// levers for cutting down the ~10x size blow-up `regenerate_pdf` otherwise
+/// produces, which matters for archiving large scanned documents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageCodec {
+    Jpeg,
+    Png,
+    WebP,
+}
+
+impl ImageCodec {
+    fn image_format(self) -> image::ImageFormat {
+        match self {
+            ImageCodec::Jpeg => image::ImageFormat::Jpeg,
+            ImageCodec::Png => image::ImageFormat::Png,
+            ImageCodec::WebP => image::ImageFormat::WebP,
+        }
+    }
+
+    fn printpdf_compression(self) -> printpdf::ImageCompression {
+        match self {
+            ImageCodec::Jpeg => printpdf::ImageCompression::Jpeg,
+            ImageCodec::Png => printpdf::ImageCompression::Png,
+            ImageCodec::WebP => printpdf::ImageCompression::Webp,
+        }
+    }
+}
+
+/// Tunables for [`regenerate_pdf_with_options`]: render DPI, output image
+/// quality/codec, page batch size, and whether to flatten to greyscale.
+/// `regenerate_pdf` uses [`RegenerationOptions::default`], which preserves
+/// the previous hardcoded behaviour (300 DPI, JPEG at quality 70, 5-page
+/// batches, no greyscale).
+#[derive(Debug, Clone, Copy)]
+pub struct RegenerationOptions {
+    pub dpi: f32,
+    pub jpg_quality: f32,
+    pub page_batch: u16,
+    pub codec: ImageCodec,
+    pub greyscale: bool,
+    pub ocr: bool,
+}
+
+impl Default for RegenerationOptions {
+    fn default() -> Self {
+        Self {
+            dpi: DPI,
+            jpg_quality: JPG_QUALITY,
+            page_batch: PAGE_BATCH,
+            codec: ImageCodec::Jpeg,
+            greyscale: false,
+            ocr: false,
+        }
+    }
+}
+
+impl RegenerationOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Render DPI. Higher values produce sharper but larger pages.
+    pub fn dpi(mut self, dpi: f32) -> Self {
+        self.dpi = dpi;
+        self
+    }
+
+    /// Output image quality, on the same 0-100 scale printpdf expects.
+    /// Ignored by codecs that don't have a quality knob.
+    pub fn jpg_quality(mut self, jpg_quality: f32) -> Self {
+        self.jpg_quality = jpg_quality;
+        self
+    }
+
+    /// How many pages to rasterise and assemble per intermediate PDF chunk
+    /// before merging, bounding peak memory usage.
+    pub fn page_batch(mut self, page_batch: u16) -> Self {
+        self.page_batch = page_batch;
+        self
+    }
+
+    /// Output image codec for both the intermediate raster and the final
+    /// embedded images.
+    pub fn codec(mut self, codec: ImageCodec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    /// Flatten rendered pages to greyscale, routed through printpdf's
+    /// `convert_to_greyscale`/`dither_greyscale`.
+    pub fn greyscale(mut self, greyscale: bool) -> Self {
+        self.greyscale = greyscale;
+        self
+    }
+
+    /// Emit an invisible OCR text layer on top of each rasterised page, so
+    /// the output stays searchable/selectable despite carrying no native
+    /// text (see [`crate::pdf::ocr`]). Off by default since OCR is by far
+    /// the most expensive step in the pipeline. Requires the `ocr` cargo
+    /// feature — ignored otherwise.
+    pub fn ocr(mut self, ocr: bool) -> Self {
+        self.ocr = ocr;
+        self
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum PDFRegenerationError {
     #[error("Input must be a valid PDF file")]
@@ -40,6 +172,41 @@ pub enum PDFRegenerationError {
     BadMerge(#[from] MergePDFError),
     #[error("Cannot manipulate PDF")]
     BadPDF(#[from] PdfiumError),
+    #[error("Pre-sanitisation threat scan failed")]
+    BadScan(#[from] ScanError),
+    #[error("Cannot structurally clean PDF")]
+    BadStructuralClean(#[from] StructuralCleanError),
+}
+
+/// Strategy for sanitising an untrusted PDF. `Rasterize` is the original,
+/// maximally-safe approach (see [`regenerate_pdf`]): every page becomes a
+/// flattened image, so no native PDF object survives, at the cost of a
+/// ~10x larger file and no selectable text. `Structural` instead keeps the
+/// document's own content streams, fonts and images intact and only strips
+/// the specific constructs that can execute or exfiltrate (see
+/// [`structural_clean`]) — smaller, and keeps text and vectors selectable,
+/// at the cost of trusting that those constructs are the only risk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SanitizationStrategy {
+    Rasterize,
+    Structural,
+}
+
+/// Sanitise `input` into `output_path` using `strategy`.
+pub fn sanitize_pdf<P>(
+    input: &P,
+    output_path: &P,
+    strategy: SanitizationStrategy,
+) -> Result<(), PDFRegenerationError>
+where
+    P: AsRef<Path>,
+{
+    match strategy {
+        SanitizationStrategy::Rasterize => regenerate_pdf(input, output_path),
+        SanitizationStrategy::Structural => {
+            structural_clean(input, output_path).map_err(PDFRegenerationError::BadStructuralClean)
+        }
+    }
 }
 
 /// Regenerate the input PDF as an entire new file.
@@ -54,15 +221,122 @@ pub fn regenerate_pdf<P>(input: &P, output_path: &P) -> Result<(), PDFRegenerati
 where
     P: AsRef<Path>,
 {
-    let pdfium = get_pdfium_instance();
+    regenerate_pdf_with_options(input, output_path, &RegenerationOptions::default())
+}
 
+/// Same as [`regenerate_pdf`], but with render DPI, output quality/codec,
+/// page batch size, and greyscale all configurable via `options` instead of
+/// hardcoded constants.
+pub fn regenerate_pdf_with_options<P>(
+    input: &P,
+    output_path: &P,
+    options: &RegenerationOptions,
+) -> Result<(), PDFRegenerationError>
+where
+    P: AsRef<Path>,
+{
     let input_filename = input
         .as_ref()
         .file_stem()
         .and_then(|f| f.to_str())
         .ok_or(PDFRegenerationError::InvalidInput)?;
 
-    let input_doc = pdfium.load_pdf_from_file(input, None)?;
+    let input_bytes = fs::read(input)?;
+    let output_bytes = regenerate_bytes(&input_bytes, Some(input_filename), options)?;
+    fs::write(output_path, output_bytes)?;
+    Ok(())
+}
+
+/// Same as [`regenerate_pdf`], but for callers holding the input PDF in
+/// memory (e.g. bytes received over a network) instead of on disk — avoids
+/// ever touching the filesystem.
+pub fn regenerate_pdf_from_bytes(input_bytes: &[u8]) -> Result<Vec<u8>, PDFRegenerationError> {
+    regenerate_pdf_from_bytes_with_options(input_bytes, &RegenerationOptions::default())
+}
+
+/// Same as [`regenerate_pdf_from_bytes`], but with `options` configurable
+/// like [`regenerate_pdf_with_options`].
+pub fn regenerate_pdf_from_bytes_with_options(
+    input_bytes: &[u8],
+    options: &RegenerationOptions,
+) -> Result<Vec<u8>, PDFRegenerationError> {
+    // There's no source filename to derive a title from here, so the merged
+    // output is left untitled.
+    regenerate_bytes(input_bytes, None, options)
+}
+
+/// Streaming variant of [`regenerate_pdf_from_bytes`]: reads the full input
+/// from `reader` and writes the sanitised output to `writer`, without ever
+/// writing either to disk. Still buffers the whole PDF in memory — there's
+/// no way to start rendering page 1 before pdfium has the complete document.
+pub fn regenerate_pdf_to_writer<R, W>(mut reader: R, mut writer: W) -> Result<(), PDFRegenerationError>
+where
+    R: Read,
+    W: Write,
+{
+    let mut input_bytes = Vec::new();
+    reader.read_to_end(&mut input_bytes)?;
+    let output_bytes = regenerate_pdf_from_bytes(&input_bytes)?;
+    writer.write_all(&output_bytes)?;
+    Ok(())
+}
+
+/// Same as [`regenerate_pdf_from_bytes`], but also scans `input_bytes` for
+/// active content (see [`crate::pdf::scan::scan_pdf`]) before rendering and
+/// returns the resulting [`ThreatReport`] alongside the sanitised output, so
+/// a caller can log what was stripped — e.g. for an audit trail.
+pub fn regenerate_pdf_from_bytes_with_report(
+    input_bytes: &[u8],
+) -> Result<(Vec<u8>, ThreatReport), PDFRegenerationError> {
+    let report = scan_pdf_bytes(input_bytes)?;
+    let output_bytes = regenerate_pdf_from_bytes(input_bytes)?;
+    Ok((output_bytes, report))
+}
+
+/// Same as [`regenerate_pdf_from_bytes_with_report`], but refuses to
+/// sanitise at all — returning [`PDFRegenerationError::BadScan`] — if the
+/// scan finds content in `blacklist`, instead of silently stripping it.
+pub fn regenerate_pdf_from_bytes_with_blacklist(
+    input_bytes: &[u8],
+    blacklist: &[ThreatCategory],
+) -> Result<(Vec<u8>, ThreatReport), PDFRegenerationError> {
+    let report = scan_pdf_bytes_with_blacklist(input_bytes, blacklist)?;
+    let output_bytes = regenerate_pdf_from_bytes(input_bytes)?;
+    Ok((output_bytes, report))
+}
+
+/// Path-based sibling of [`regenerate_pdf_from_bytes_with_report`]: writes
+/// the sanitised output to `output_path`, same as [`regenerate_pdf`], and
+/// returns the pre-sanitisation [`ThreatReport`].
+pub fn regenerate_pdf_with_report<P>(
+    input: &P,
+    output_path: &P,
+) -> Result<ThreatReport, PDFRegenerationError>
+where
+    P: AsRef<Path>,
+{
+    let input_bytes = fs::read(input)?;
+    let report = scan_pdf_bytes(&input_bytes)?;
+    regenerate_pdf(input, output_path)?;
+    Ok(report)
+}
+
+/// Core of [`regenerate_pdf_with_options`] and
+/// [`regenerate_pdf_from_bytes_with_options`]: renders `input_bytes` page by
+/// page, accumulating each `page_batch`-sized chunk as an in-memory PDF
+/// buffer, then merges those buffers directly via [`merge_pdf_bytes`] —
+/// nothing is ever spilled to a temp file.
+fn regenerate_bytes(
+    input_bytes: &[u8],
+    title: Option<&str>,
+    options: &RegenerationOptions,
+) -> Result<Vec<u8>, PDFRegenerationError> {
+    let pdfium_guard = shared_pdfium()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let pdfium: &Pdfium = &pdfium_guard;
+
+    let input_doc = pdfium.load_pdf_from_byte_slice(input_bytes, None)?;
     let pages = input_doc.pages();
 
     let input_doc_length: u16 = pages.len();
@@ -71,31 +345,34 @@ where
         return Err(PDFRegenerationError::EmptyInput);
     }
 
-    // We process at most the PAGE_BATCH page count at each loop iteration.
-    // In case the original PDF is smaller than that, then we can fallback to its page count.
-    // This is necessary so we keep memory pressure low across all threads using this function
-    let chunk_processing_size = if input_doc_length > PAGE_BATCH {
-        PAGE_BATCH
+    // Floor at 1: a `page_batch` of 0 would make `chunk_processing_size`/`top`
+    // both evaluate to 0 and `processed_pages_count` never advance, spinning
+    // the loop below forever. Mirrors `split_pdf_into_chunks`'s same guard.
+    let page_batch = options.page_batch.max(1);
+
+    // We process at most `page_batch` pages at each loop iteration. In case
+    // the original PDF is smaller than that, then we can fallback to its
+    // page count. This is necessary so we keep memory pressure low across
+    // all threads using this function
+    let chunk_processing_size = if input_doc_length > page_batch {
+        page_batch
     } else {
         input_doc_length
     };
 
     let mut processed_pages_count = 0;
-    let mut written_chuncks_count = 0;
-    let mut temp_pdf_files: Vec<_> = Vec::new();
+    let mut chunk_buffers: Vec<Vec<u8>> = Vec::new();
     let mut bitmap_container: Option<PdfBitmap> = None;
-    // Unique identifier for prefixing the temporary cache files.
-    // This allows us to prevent any clasing in case consumers
-    // are sanitizing the same file at once.
-    let unique_temp_id = Uuid::new_v4();
 
     while processed_pages_count < input_doc_length {
         let mut pdf_pages = Vec::with_capacity(chunk_processing_size as usize);
         let mut doc_out = PdfDocument::new("Clean PDF Document");
+        #[cfg(feature = "ocr")]
+        let ocr_font = options.ocr.then(|| ocr::add_ocr_font(&mut doc_out));
         let local_acc: u16 = processed_pages_count;
         // Cap the trailing end of the range at maximum the batch size
         // or how many pages are left in case they are smaller than the batch size
-        let top: u16 = local_acc + min(PAGE_BATCH, input_doc_length - local_acc);
+        let top: u16 = local_acc + min(page_batch, input_doc_length - local_acc);
 
         for index in local_acc..top {
             let page = pages.get(index)?;
@@ -105,8 +382,8 @@ where
             let height_pts = page.page_size().height().value; // f32
 
             // Calculate target pixel dimensions for the desired DPI
-            let target_render_width = (width_pts * DPI / 72.0).round() as i32;
-            let target_render_height = (height_pts * DPI / 72.0).round() as i32;
+            let target_render_width = (width_pts * options.dpi / 72.0).round() as i32;
+            let target_render_height = (height_pts * options.dpi / 72.0).round() as i32;
 
             // Make sure we have a pre-allocated container for the given page dimensions
             match &bitmap_container {
@@ -138,16 +415,16 @@ where
 
             // Rasterize the page at the new higher resolution
             let bitmap = rendering_container.as_image().to_rgb8();
-            let mut jpg_data = Vec::new();
+            let mut image_data = Vec::new();
 
-            bitmap.write_to(&mut Cursor::new(&mut jpg_data), image::ImageFormat::Jpeg)?;
+            bitmap.write_to(&mut Cursor::new(&mut image_data), options.codec.image_format())?;
             // Put back the reusable rendering container
             // So we can reference it again on the next loop run
             // preventing allocating another buffer
             bitmap_container = Some(rendering_container);
 
             let mut warnings = Vec::new();
-            let image = RawImage::decode_from_bytes(&jpg_data, &mut warnings)
+            let image = RawImage::decode_from_bytes(&image_data, &mut warnings)
                 .map_err(PDFRegenerationError::BadImageDecoding)?;
 
             let image_id = doc_out.add_image(&image);
@@ -156,11 +433,19 @@ where
             let width_mm = Mm(width_pts * 25.4 / 72.0);
             let height_mm = Mm(height_pts * 25.4 / 72.0);
 
-            let contents = vec![Op::UseXobject {
+            let mut contents = vec![Op::UseXobject {
                 id: image_id,
                 transform: XObjectTransform::default(),
             }];
 
+            #[cfg(feature = "ocr")]
+            if let Some(font) = &ocr_font {
+                match ocr::ocr_text_layer_ops(&image_data, options.dpi, height_mm.0, font.clone()) {
+                    Ok(mut ocr_ops) => contents.append(&mut ocr_ops),
+                    Err(e) => println!("OCR text layer failed for page {index}: {e}"),
+                }
+            }
+
             println!("Page {index} regenerated");
             let pdf_page = PdfPage::new(width_mm, height_mm, contents);
             pdf_pages.push(pdf_page);
@@ -173,119 +458,33 @@ where
             subset_fonts: true,
             image_optimization: Some(ImageOptimizationOptions {
                 auto_optimize: Some(true),
-                convert_to_greyscale: Some(false),
-                dither_greyscale: None,
+                convert_to_greyscale: Some(options.greyscale),
+                dither_greyscale: Some(options.greyscale),
                 max_image_size: None,
-                format: Some(printpdf::ImageCompression::Jpeg),
-                quality: Some(JPG_QUALITY),
+                format: Some(options.codec.printpdf_compression()),
+                quality: Some(options.jpg_quality),
             }),
         };
 
         let pdf_bytes = doc_out.with_pages(pdf_pages).save(&opts, &mut warnings);
 
-        let filename =
-            format!("{input_filename}_temp_file_{unique_temp_id}_{written_chuncks_count}.pdf");
-        let mut temp_file = env::temp_dir();
-        temp_file.push(filename);
-
-        let mut file = File::create(&temp_file)?;
-        file.write_all(&pdf_bytes)?;
-
-        temp_pdf_files.push(temp_file);
-        written_chuncks_count += 1;
-        processed_pages_count += PAGE_BATCH
+        chunk_buffers.push(pdf_bytes);
+        processed_pages_count += page_batch
     }
 
-    match merge_pdf_files(&temp_pdf_files, &PathBuf::from(output_path.as_ref())) {
-        Ok(()) => {
-            // Clean-up the temp files once we generate the final one
-            clean_up_temp_files(&temp_pdf_files);
-            Ok(())
-        }
-        Err(e) => {
-            clean_up_temp_files(&temp_pdf_files);
-            Err(PDFRegenerationError::BadMerge(e))
-        }
-    }
-}
-
-/// Delete the given files
-/// Failure to remove them should not halt the process
-fn clean_up_temp_files(files: &[PathBuf]) {
-    files.iter().for_each(|f| {
-        if let Err(e) = fs::remove_file(f) {
-            eprintln!("Could not delete temp file. error={e}")
-        }
-    });
-}
-
-// For the sake of simplicity, we only Support Mac (ARM64) and Linux (AMD 64-bit)
-enum SupportArch {
-    MacOS,
-    Linux,
-}
-
-fn _get_pdfium_instance(arch: SupportArch) -> Pdfium {
-    let lib_arch = match arch {
-        SupportArch::MacOS => "macOS",
-        SupportArch::Linux => "linux-x64",
-    };
-
-    // Make sure that resources/pdfium/<arch>/lib is available in production
-    let lib_path = std::env::current_dir().expect("Could not get the current dir path");
-
-    let runtime_lib_path = lib_path
-        .join("resources")
-        .join("pdfium")
-        .join(lib_arch)
-        .join("lib");
-
-    // When executing this library from Cargo, we must use
-    // resources under the crate's folder
-    let mut crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-    crate_dir.pop();
-    let crate_dir = crate_dir
-        .join("resources")
-        .join("pdfium")
-        .join(lib_arch)
-        .join("lib");
-
-    Pdfium::new(
-        Pdfium::bind_to_library(Pdfium::pdfium_platform_library_name_at_path(
-            &runtime_lib_path,
-        ))
-        .or_else(|_| {
-            println!("Binding to crate dir");
-            Pdfium::bind_to_library(Pdfium::pdfium_platform_library_name_at_path(&crate_dir))
-        })
-        .or_else(|_| {
-            println!("Binding to system");
-            Pdfium::bind_to_system_library()
-        })
-        .unwrap(),
-    )
-}
-
-// Bind to the library at a specific path during runtime.
-// Panics if PDFium isn't available during runtime.
-#[cfg(target_os = "macos")]
-fn get_pdfium_instance() -> Pdfium {
-    _get_pdfium_instance(SupportArch::MacOS)
+    merge_pdf_bytes(&chunk_buffers, title, None).map_err(PDFRegenerationError::BadMerge)
 }
 
-// Bind to the library at a specific path during runtime.
-// Panics if PDFium isn't available during runtime.
-#[cfg(target_os = "linux")]
-fn get_pdfium_instance() -> Pdfium {
-    _get_pdfium_instance(SupportArch::Linux)
-}
-
-// On other platforms, we can try to use the system library directly.
-// It will panic in case PDFium isn't installed.
-// Sorry Windows folks...
-#[cfg(not(any(target_os = "macos", target_os = "linux")))]
-fn get_pdfium_instance() -> Pdfium {
-    Pdfium::new(Pdfium::bind_to_system_library())
+/// `async`-friendly wrapper around [`regenerate_pdf`] for callers on a tokio
+/// runtime: runs the CPU-heavy render loop inside `spawn_blocking` so it
+/// doesn't block the executor while it holds `shared_pdfium()`'s lock.
+pub async fn regenerate_pdf_async<P>(input: P, output_path: P) -> Result<(), PDFRegenerationError>
+where
+    P: AsRef<Path> + Send + 'static,
+{
+    tokio::task::spawn_blocking(move || regenerate_pdf(&input, &output_path))
+        .await
+        .unwrap_or_else(|e| Err(PDFRegenerationError::InvalidFile(io::Error::other(e))))
 }
 
 #[cfg(test)]
@@ -572,4 +771,79 @@ mod tests {
             "Batch processed PDF should pass integrity check with pre-sanitised file"
         );
     }
+
+    #[test]
+    fn test_regenerate_pdf_from_bytes() {
+        let input = get_test_pdf_path("annotations-test.pdf");
+        let input_bytes = std::fs::read(&input).expect("Failed to read input file");
+
+        let result = regenerate_pdf_from_bytes(&input_bytes);
+        assert!(
+            result.is_ok(),
+            "Failed to regenerate PDF from bytes: {result:?}"
+        );
+
+        let output_doc = Document::load_mem(&result.unwrap());
+        assert!(output_doc.is_ok(), "Output is not a valid PDF");
+
+        let original_file = File::open(&input).expect("Failed to open original file");
+        let original_doc = Document::load_from(original_file).expect("Failed to load original PDF");
+
+        assert_eq!(
+            original_doc.get_pages().len(),
+            output_doc.unwrap().get_pages().len(),
+            "Regenerated PDF should have the same number of pages as original"
+        );
+    }
+
+    #[test]
+    fn test_regenerate_pdf_to_writer() {
+        let input = get_test_pdf_path("page-sizes-test.pdf");
+        let input_file = File::open(&input).expect("Failed to open input file");
+
+        let mut output_bytes = Vec::new();
+        let result = regenerate_pdf_to_writer(input_file, &mut output_bytes);
+        assert!(
+            result.is_ok(),
+            "Failed to regenerate PDF to writer: {result:?}"
+        );
+
+        let output_doc = Document::load_mem(&output_bytes);
+        assert!(output_doc.is_ok(), "Output is not a valid PDF");
+    }
+
+    #[test]
+    fn test_regenerate_pdf_from_bytes_with_report() {
+        let input = get_test_pdf_path("page-sizes-test.pdf");
+        let input_bytes = std::fs::read(&input).expect("Failed to read input file");
+
+        let result = regenerate_pdf_from_bytes_with_report(&input_bytes);
+        assert!(result.is_ok(), "Failed to regenerate with report: {result:?}");
+
+        let (output_bytes, report) = result.unwrap();
+        assert!(
+            Document::load_mem(&output_bytes).is_ok(),
+            "Output is not a valid PDF"
+        );
+        assert!(
+            report.is_clean(),
+            "Plain test fixture should have no active content, found: {:?}",
+            report.findings
+        );
+    }
+
+    #[test]
+    fn test_regenerate_pdf_from_bytes_with_blacklist_allows_clean_file() {
+        let input = get_test_pdf_path("page-sizes-test.pdf");
+        let input_bytes = std::fs::read(&input).expect("Failed to read input file");
+
+        let result = regenerate_pdf_from_bytes_with_blacklist(
+            &input_bytes,
+            &[ThreatCategory::JavaScript, ThreatCategory::Launch],
+        );
+        assert!(
+            result.is_ok(),
+            "Should not reject a document with no blacklisted content: {result:?}"
+        );
+    }
 }
@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use thiserror::Error;
+
+/// A category of active content that [`scan_pdf`] looks for before a
+/// document is rasterised. `regenerate_pdf` strips all of these
+/// unconditionally by re-drawing every page as a flat image; this scan
+/// exists purely so a caller can log (or refuse) what was present
+/// beforehand, e.g. for an audit trail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ThreatCategory {
+    /// Document-level `/OpenAction`: runs automatically when the file opens.
+    OpenAction,
+    /// A document- or page-level `/AA` additional-action dictionary.
+    AdditionalAction,
+    /// A `/JavaScript` name-tree entry or a `/JS` action stream.
+    JavaScript,
+    /// A `/Launch` action, which can run an external program or file.
+    Launch,
+    /// A `/URI` action, which can silently exfiltrate data via a GET request.
+    Uri,
+    /// A `/SubmitForm` action, which can exfiltrate form data to a URL.
+    SubmitForm,
+    /// An `/EmbeddedFiles` name-tree entry or `/FileAttachment` annotation.
+    EmbeddedFile,
+}
+
+/// One piece of active content found by [`scan_pdf`]: which category it
+/// falls into, the object it was found on, and a human-readable location.
+#[derive(Debug, Clone)]
+pub struct ThreatFinding {
+    pub category: ThreatCategory,
+    pub object_id: ObjectId,
+    pub location: String,
+}
+
+/// Report produced by [`scan_pdf`]: every piece of active content found
+/// while walking the document's catalog, pages, and objects.
+#[derive(Debug, Clone, Default)]
+pub struct ThreatReport {
+    pub findings: Vec<ThreatFinding>,
+}
+
+impl ThreatReport {
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Number of findings in `category`.
+    pub fn count(&self, category: ThreatCategory) -> usize {
+        self.findings
+            .iter()
+            .filter(|finding| finding.category == category)
+            .count()
+    }
+
+    /// Per-category breakdown of `findings`, omitting categories with zero
+    /// hits.
+    pub fn counts_by_category(&self) -> HashMap<ThreatCategory, usize> {
+        let mut counts = HashMap::new();
+        for finding in &self.findings {
+            *counts.entry(finding.category).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ScanError {
+    #[error("Cannot open file")]
+    InvalidFile(#[from] std::io::Error),
+    #[error("Cannot parse PDF")]
+    InvalidPdf(#[from] lopdf::Error),
+    #[error("Document contains blacklisted content: {0:?}")]
+    BlacklistedContentFound(ThreatCategory),
+}
+
+/// Scan `input` for active content before it's sanitised, returning a
+/// [`ThreatReport`] of everything found. This never errors on what it
+/// finds — see [`scan_pdf_with_blacklist`] for a variant that refuses a
+/// document outright when it contains a disallowed category.
+pub fn scan_pdf<P: AsRef<Path>>(input: &P) -> Result<ThreatReport, ScanError> {
+    let bytes = fs::read(input)?;
+    scan_pdf_bytes(&bytes)
+}
+
+/// Same as [`scan_pdf`], for callers already holding the PDF's bytes in
+/// memory.
+pub fn scan_pdf_bytes(input_bytes: &[u8]) -> Result<ThreatReport, ScanError> {
+    let doc = Document::load_mem(input_bytes)?;
+    Ok(scan_document(&doc))
+}
+
+/// Same as [`scan_pdf`], but returns
+/// [`ScanError::BlacklistedContentFound`] instead of a report as soon as a
+/// finding falls into `blacklist`, rather than silently sanitising it away.
+pub fn scan_pdf_with_blacklist<P: AsRef<Path>>(
+    input: &P,
+    blacklist: &[ThreatCategory],
+) -> Result<ThreatReport, ScanError> {
+    let report = scan_pdf(input)?;
+    reject_if_blacklisted(&report, blacklist)?;
+    Ok(report)
+}
+
+/// Same as [`scan_pdf_with_blacklist`], for callers already holding the
+/// PDF's bytes in memory.
+pub fn scan_pdf_bytes_with_blacklist(
+    input_bytes: &[u8],
+    blacklist: &[ThreatCategory],
+) -> Result<ThreatReport, ScanError> {
+    let report = scan_pdf_bytes(input_bytes)?;
+    reject_if_blacklisted(&report, blacklist)?;
+    Ok(report)
+}
+
+fn reject_if_blacklisted(
+    report: &ThreatReport,
+    blacklist: &[ThreatCategory],
+) -> Result<(), ScanError> {
+    match report
+        .findings
+        .iter()
+        .find(|finding| blacklist.contains(&finding.category))
+    {
+        Some(finding) => Err(ScanError::BlacklistedContentFound(finding.category)),
+        None => Ok(()),
+    }
+}
+
+fn scan_document(doc: &Document) -> ThreatReport {
+    let mut findings = Vec::new();
+
+    let catalog_id = doc.trailer.get(b"Root").and_then(Object::as_reference).ok();
+
+    if let Ok(catalog) = doc.catalog() {
+        scan_catalog(doc, catalog, catalog_id, &mut findings);
+    }
+
+    for (_, page_id) in doc.get_pages() {
+        if let Ok(page) = doc.get_object(page_id).and_then(Object::as_dict)
+            && page.has(b"AA")
+        {
+            findings.push(ThreatFinding {
+                category: ThreatCategory::AdditionalAction,
+                object_id: page_id,
+                location: format!("page object {}.{}", page_id.0, page_id.1),
+            });
+        }
+    }
+
+    for (&object_id, object) in doc.objects.iter() {
+        scan_object(object_id, object, &mut findings);
+    }
+
+    ThreatReport { findings }
+}
+
+/// Document-level entries only visible from the catalog: `/OpenAction`,
+/// `/AA`, and the `/Names/JavaScript` and `/Names/EmbeddedFiles` name trees.
+fn scan_catalog(
+    doc: &Document,
+    catalog: &Dictionary,
+    catalog_id: Option<ObjectId>,
+    findings: &mut Vec<ThreatFinding>,
+) {
+    let object_id = catalog_id.unwrap_or((0, 0));
+    let location = "document catalog".to_string();
+
+    if catalog.has(b"OpenAction") {
+        findings.push(ThreatFinding {
+            category: ThreatCategory::OpenAction,
+            object_id,
+            location: location.clone(),
+        });
+    }
+
+    if catalog.has(b"AA") {
+        findings.push(ThreatFinding {
+            category: ThreatCategory::AdditionalAction,
+            object_id,
+            location: location.clone(),
+        });
+    }
+
+    let Some(names) = catalog
+        .get(b"Names")
+        .ok()
+        .and_then(|names_ref| resolve(doc, names_ref))
+        .and_then(|names_obj| names_obj.as_dict().ok())
+    else {
+        return;
+    };
+
+    if names.has(b"JavaScript") {
+        findings.push(ThreatFinding {
+            category: ThreatCategory::JavaScript,
+            object_id,
+            location: "document Names/JavaScript tree".to_string(),
+        });
+    }
+
+    if names.has(b"EmbeddedFiles") {
+        findings.push(ThreatFinding {
+            category: ThreatCategory::EmbeddedFile,
+            object_id,
+            location: "document Names/EmbeddedFiles tree".to_string(),
+        });
+    }
+}
+
+/// Action/annotation dictionaries that can appear anywhere in the object
+/// table: `/JS` action streams, `/Launch`, `/URI` and `/SubmitForm` actions,
+/// and `/FileAttachment` annotations.
+fn scan_object(object_id: ObjectId, object: &Object, findings: &mut Vec<ThreatFinding>) {
+    let dict = match object {
+        Object::Dictionary(dict) => dict,
+        Object::Stream(stream) => &stream.dict,
+        _ => return,
+    };
+    let location = format!("object {}.{}", object_id.0, object_id.1);
+
+    if dict.has(b"JS") {
+        findings.push(ThreatFinding {
+            category: ThreatCategory::JavaScript,
+            object_id,
+            location: location.clone(),
+        });
+    }
+
+    if let Ok(action_type) = dict.get(b"S").and_then(Object::as_name) {
+        let category = match action_type {
+            b"Launch" => Some(ThreatCategory::Launch),
+            b"URI" => Some(ThreatCategory::Uri),
+            b"SubmitForm" => Some(ThreatCategory::SubmitForm),
+            _ => None,
+        };
+        if let Some(category) = category {
+            findings.push(ThreatFinding {
+                category,
+                object_id,
+                location: location.clone(),
+            });
+        }
+    }
+
+    if let Ok(subtype) = dict.get(b"Subtype").and_then(Object::as_name)
+        && subtype == b"FileAttachment"
+    {
+        findings.push(ThreatFinding {
+            category: ThreatCategory::EmbeddedFile,
+            object_id,
+            location,
+        });
+    }
+}
+
+/// Resolve `object`, following one level of indirection if it's a
+/// reference.
+fn resolve<'a>(doc: &'a Document, object: &'a Object) -> Option<&'a Object> {
+    match object {
+        Object::Reference(id) => doc.get_object(*id).ok(),
+        other => Some(other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn get_test_pdf_path(filename: &str) -> PathBuf {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.pop(); // Go up from sanitiser/ to workspace root
+        path.push("tests");
+        path.push(filename);
+        path
+    }
+
+    #[test]
+    fn test_scan_pdf_clean_document() {
+        let input = get_test_pdf_path("page-sizes-test.pdf");
+        let report = scan_pdf(&input).expect("Failed to scan PDF");
+        assert!(
+            report.is_clean(),
+            "Plain PDF should have no active content, found: {:?}",
+            report.findings
+        );
+    }
+
+    #[test]
+    fn test_scan_pdf_nonexistent_file() {
+        let nonexistent = PathBuf::from("/path/that/does/not/exist.pdf");
+        let result = scan_pdf(&nonexistent);
+        assert!(result.is_err(), "Should return error for nonexistent file");
+    }
+
+    #[test]
+    fn test_scan_pdf_with_blacklist_allows_when_absent() {
+        let input = get_test_pdf_path("page-sizes-test.pdf");
+        let result = scan_pdf_with_blacklist(&input, &[ThreatCategory::JavaScript]);
+        assert!(
+            result.is_ok(),
+            "Should not reject a document with no blacklisted content"
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use lopdf::Document;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// Split `input_path` into a sequence of `chunk_pages`-page PDF files written
+/// into `work_dir`, in page order. The last chunk is shorter than the rest
+/// when the page count isn't evenly divisible by `chunk_pages`.
+///
+/// This exists so callers (namely the job worker's split/scatter/gather
+/// pipeline) can fan chunks out to separate `procspawn` children instead of
+/// serialising a whole document through a single one.
+pub fn split_pdf_into_chunks(
+    input_path: &Path,
+    chunk_pages: u16,
+    work_dir: &Path,
+) -> Result<Vec<PathBuf>, String> {
+    let file = File::open(input_path)
+        .map_err(|e| format!("Could not open input file for splitting. error={e}"))?;
+    let doc = Document::load_from(file)
+        .map_err(|e| format!("Could not parse input PDF for splitting. error={e}"))?;
+
+    let mut page_numbers: Vec<u32> = doc.get_pages().keys().copied().collect();
+    page_numbers.sort_unstable();
+
+    if page_numbers.is_empty() {
+        return Err("Input PDF has no pages to split".to_string());
+    }
+
+    let stem = input_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("chunk");
+
+    let mut chunk_paths = Vec::new();
+    for (chunk_index, pages) in page_numbers.chunks(chunk_pages.max(1) as usize).enumerate() {
+        let mut chunk_doc = doc.clone();
+        let pages_to_remove: Vec<u32> = page_numbers
+            .iter()
+            .copied()
+            .filter(|page_num| !pages.contains(page_num))
+            .collect();
+        chunk_doc.delete_pages(&pages_to_remove);
+
+        let chunk_path = work_dir.join(format!("{stem}_chunk_{chunk_index}.pdf"));
+        chunk_doc
+            .save(&chunk_path)
+            .map_err(|e| format!("Could not write PDF chunk. error={e}"))?;
+        chunk_paths.push(chunk_path);
+    }
+
+    Ok(chunk_paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::Document as LoDocument;
+
+    fn get_test_pdf_path(filename: &str) -> PathBuf {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.pop();
+        path.push("tests");
+        path.push(filename);
+        path
+    }
+
+    #[test]
+    fn test_split_into_single_chunk_when_pages_fit() {
+        let input = get_test_pdf_path("page-sizes-test.pdf");
+        let work_dir = std::env::temp_dir();
+
+        let chunks = split_pdf_into_chunks(&input, 5, &work_dir).expect("Failed to split PDF");
+        assert_eq!(chunks.len(), 1, "Single-page PDF should produce one chunk");
+
+        for chunk in &chunks {
+            let file = File::open(chunk).expect("Failed to open chunk");
+            assert!(LoDocument::load_from(file).is_ok(), "Chunk should be a valid PDF");
+            std::fs::remove_file(chunk).ok();
+        }
+    }
+
+    #[test]
+    fn test_split_preserves_total_page_count() {
+        let input = get_test_pdf_path("annotations-test.pdf");
+        let work_dir = std::env::temp_dir();
+
+        let original_file = File::open(&input).expect("Failed to open original file");
+        let original_doc = LoDocument::load_from(original_file).expect("Failed to load original PDF");
+        let total_pages = original_doc.get_pages().len();
+
+        let chunks = split_pdf_into_chunks(&input, 1, &work_dir).expect("Failed to split PDF");
+        assert_eq!(chunks.len(), total_pages, "Should produce one chunk per page");
+
+        let mut pages_seen = 0;
+        for chunk in &chunks {
+            let file = File::open(chunk).expect("Failed to open chunk");
+            let doc = LoDocument::load_from(file).expect("Chunk should be a valid PDF");
+            pages_seen += doc.get_pages().len();
+            std::fs::remove_file(chunk).ok();
+        }
+        assert_eq!(pages_seen, total_pages);
+    }
+
+    #[test]
+    fn test_split_nonexistent_file() {
+        let nonexistent = PathBuf::from("/path/that/does/not/exist.pdf");
+        let result = split_pdf_into_chunks(&nonexistent, 5, &std::env::temp_dir());
+        assert!(result.is_err(), "Should return error for nonexistent file");
+    }
+}
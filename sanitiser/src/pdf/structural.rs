@@ -0,0 +1,210 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use lopdf::{Document, Object, ObjectId};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum StructuralCleanError {
+    #[error("Cannot open file")]
+    InvalidFile(#[from] std::io::Error),
+    #[error("Cannot parse PDF")]
+    InvalidPdf(#[from] lopdf::Error),
+}
+
+/// Alternative to [`crate::pdf::sanitise::regenerate_pdf`] that trades some
+/// safety margin for fidelity: instead of rasterising every page, it keeps
+/// the document's own content streams, fonts and images intact and only
+/// strips the specific constructs that can execute code or exfiltrate data
+/// (`/OpenAction`, `/AA`, the `/JavaScript` name tree, `/Launch`/`/URI`/
+/// `/SubmitForm` actions, and embedded files), then drops whatever becomes
+/// unreachable from the catalog as a result.
+///
+/// This does not subset fonts — lopdf has no font-subsetting support, and
+/// this crate doesn't depend on a dedicated subsetting library, so embedded
+/// fonts are carried over as-is.
+pub fn structural_clean<P>(input: &P, output_path: &P) -> Result<(), StructuralCleanError>
+where
+    P: AsRef<Path>,
+{
+    let input_bytes = fs::read(input)?;
+    let output_bytes = structural_clean_bytes(&input_bytes)?;
+    fs::write(output_path, output_bytes)?;
+    Ok(())
+}
+
+/// Same as [`structural_clean`], for callers already holding the PDF's
+/// bytes in memory.
+pub fn structural_clean_bytes(input_bytes: &[u8]) -> Result<Vec<u8>, StructuralCleanError> {
+    let mut doc = Document::load_mem(input_bytes)?;
+
+    strip_active_content(&mut doc);
+    sweep_unreachable_objects(&mut doc);
+    doc.compress();
+
+    let mut output = Vec::new();
+    doc.save_to(&mut output)?;
+    Ok(output)
+}
+
+/// Remove or neuter every high-risk construct in `doc`: document-level
+/// `/OpenAction` and `/AA`, the `/Names/JavaScript` and `/Names/EmbeddedFiles`
+/// name trees, page-level `/AA`, and any action dictionary anywhere in the
+/// object table whose `/S` is `Launch`, `URI`, `SubmitForm` or `JavaScript`
+/// (or that carries a `/JS` entry directly). Action dictionaries are cleared
+/// in place rather than unlinked, since other objects (annotations, form
+/// fields, the outline tree) may hold a direct reference to them — an empty
+/// dictionary is inert either way, and the reachability sweep that follows
+/// takes care of anything that does become orphaned (the `/JavaScript` and
+/// `/EmbeddedFiles` streams in particular).
+fn strip_active_content(doc: &mut Document) {
+    // The `/Names` dictionary is usually inline under the catalog, but can
+    // be an indirect reference; handle both without holding two mutable
+    // borrows of `doc` at once.
+    let mut indirect_names_id: Option<ObjectId> = None;
+
+    if let Ok(catalog) = doc.catalog_mut() {
+        catalog.remove(b"OpenAction");
+        catalog.remove(b"AA");
+
+        match catalog.get(b"Names") {
+            Ok(Object::Reference(id)) => indirect_names_id = Some(*id),
+            Ok(Object::Dictionary(names)) => {
+                let mut names = names.clone();
+                names.remove(b"JavaScript");
+                names.remove(b"EmbeddedFiles");
+                catalog.set("Names", Object::Dictionary(names));
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(names_id) = indirect_names_id
+        && let Ok(names) = doc.get_object_mut(names_id).and_then(Object::as_dict_mut)
+    {
+        names.remove(b"JavaScript");
+        names.remove(b"EmbeddedFiles");
+    }
+
+    let page_ids: Vec<ObjectId> = doc.get_pages().values().copied().collect();
+    for page_id in page_ids {
+        if let Ok(page) = doc.get_object_mut(page_id).and_then(Object::as_dict_mut) {
+            page.remove(b"AA");
+        }
+    }
+
+    let object_ids: Vec<ObjectId> = doc.objects.keys().copied().collect();
+    for object_id in object_ids {
+        let Ok(object) = doc.get_object_mut(object_id) else {
+            continue;
+        };
+        let dict = match object {
+            Object::Dictionary(dict) => dict,
+            Object::Stream(stream) => &mut stream.dict,
+            _ => continue,
+        };
+
+        let is_risky_action = dict.has(b"JS")
+            || matches!(
+                dict.get(b"S").and_then(Object::as_name),
+                Ok(b"Launch" | b"URI" | b"SubmitForm" | b"JavaScript")
+            );
+        let is_file_attachment =
+            matches!(dict.get(b"Subtype").and_then(Object::as_name), Ok(b"FileAttachment"));
+
+        if is_risky_action || is_file_attachment {
+            *dict = lopdf::Dictionary::new();
+        }
+    }
+}
+
+/// Drop every object not reachable from the document catalog by following
+/// references, arrays and dictionary values — the structural equivalent of
+/// `regenerate_pdf`'s complete rebuild, but applied to the existing object
+/// table instead of a freshly rendered one.
+fn sweep_unreachable_objects(doc: &mut Document) {
+    let Some(root_id) = doc
+        .trailer
+        .get(b"Root")
+        .ok()
+        .and_then(|root| root.as_reference().ok())
+    else {
+        return;
+    };
+
+    let mut reachable = HashSet::new();
+    let mut stack = vec![root_id];
+    while let Some(object_id) = stack.pop() {
+        if !reachable.insert(object_id) {
+            continue;
+        }
+        if let Ok(object) = doc.get_object(object_id) {
+            collect_references(object, &mut stack);
+        }
+    }
+
+    doc.objects.retain(|object_id, _| reachable.contains(object_id));
+}
+
+fn collect_references(object: &Object, stack: &mut Vec<ObjectId>) {
+    match object {
+        Object::Reference(id) => stack.push(*id),
+        Object::Array(items) => items.iter().for_each(|item| collect_references(item, stack)),
+        Object::Dictionary(dict) => dict
+            .iter()
+            .for_each(|(_, value)| collect_references(value, stack)),
+        Object::Stream(stream) => stream
+            .dict
+            .iter()
+            .for_each(|(_, value)| collect_references(value, stack)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn get_test_pdf_path(filename: &str) -> PathBuf {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.pop(); // Go up from sanitiser/ to workspace root
+        path.push("tests");
+        path.push(filename);
+        path
+    }
+
+    #[test]
+    fn test_structural_clean_preserves_page_count() {
+        let input = get_test_pdf_path("annotations-test.pdf");
+        let input_bytes = std::fs::read(&input).expect("Failed to read input file");
+
+        let result = structural_clean_bytes(&input_bytes);
+        assert!(result.is_ok(), "Failed to structurally clean PDF: {result:?}");
+
+        let output_doc = Document::load_mem(&result.unwrap()).expect("Output is not a valid PDF");
+        let input_doc = Document::load_mem(&input_bytes).expect("Failed to load original PDF");
+
+        assert_eq!(
+            input_doc.get_pages().len(),
+            output_doc.get_pages().len(),
+            "Structural clean should not change the page count"
+        );
+    }
+
+    #[test]
+    fn test_structural_clean_drops_open_action() {
+        let input = get_test_pdf_path("page-sizes-test.pdf");
+        let input_bytes = std::fs::read(&input).expect("Failed to read input file");
+
+        let result = structural_clean_bytes(&input_bytes).expect("Failed to structurally clean PDF");
+        let output_doc = Document::load_mem(&result).expect("Output is not a valid PDF");
+
+        let catalog = output_doc.catalog().expect("Missing catalog");
+        assert!(
+            !catalog.has(b"OpenAction"),
+            "Structural clean should strip /OpenAction"
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use aws_sdk_s3::Client as S3Client;
+
+/// Where to read PDF bytes from: a local path, or an S3 object addressed by
+/// an `s3://bucket/key` URI. `lopdf`/pdfium need a seekable reader over the
+/// whole document, so both variants resolve to an in-memory buffer rather
+/// than exposing a streaming `AsyncRead`.
+#[derive(Debug, Clone)]
+pub enum Source {
+    Local(PathBuf),
+    S3 { bucket: String, key: String },
+}
+
+/// Where to write regenerated PDF bytes to. Mirrors [`Source`].
+#[derive(Debug, Clone)]
+pub enum Sink {
+    Local(PathBuf),
+    S3 { bucket: String, key: String },
+}
+
+/// Region/endpoint for the S3 client backing the `S3` variants of
+/// [`Source`]/[`Sink`]. Mirrors `web_server::storage::S3StorageConfig`;
+/// credentials themselves are resolved the standard AWS SDK way
+/// (environment, instance profile, ...) rather than passed explicitly.
+#[derive(Debug, Clone, Default)]
+pub struct ObjectStorageConfig {
+    pub region: Option<String>,
+    /// Override for S3-compatible stores that aren't AWS itself (MinIO,
+    /// R2, etc). `None` uses the AWS SDK's regional default.
+    pub endpoint: Option<String>,
+}
+
+fn parse_s3_uri(raw: &str) -> Option<(String, String)> {
+    let rest = raw.strip_prefix("s3://")?;
+    let (bucket, key) = rest.split_once('/').unwrap_or((rest, ""));
+    Some((bucket.to_string(), key.to_string()))
+}
+
+impl Source {
+    /// Parses `s3://bucket/key` into [`Source::S3`]; anything else is
+    /// treated as a local filesystem path.
+    pub fn parse(raw: &str) -> Self {
+        match parse_s3_uri(raw) {
+            Some((bucket, key)) => Source::S3 { bucket, key },
+            None => Source::Local(PathBuf::from(raw)),
+        }
+    }
+
+    /// Reads the whole object into memory.
+    pub async fn read(&self, config: &ObjectStorageConfig) -> Result<Vec<u8>> {
+        match self {
+            Source::Local(path) => {
+                std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))
+            }
+            Source::S3 { bucket, key } => {
+                let client = s3_client(config).await?;
+                let output = client
+                    .get_object()
+                    .bucket(bucket)
+                    .key(key)
+                    .send()
+                    .await
+                    .with_context(|| format!("Failed to get s3://{bucket}/{key}"))?;
+                let body = output
+                    .body
+                    .collect()
+                    .await
+                    .with_context(|| format!("Failed to read body of s3://{bucket}/{key}"))?;
+                Ok(body.into_bytes().to_vec())
+            }
+        }
+    }
+}
+
+impl Sink {
+    /// Parses `s3://bucket/key` into [`Sink::S3`]; anything else is treated
+    /// as a local filesystem path.
+    pub fn parse(raw: &str) -> Self {
+        match parse_s3_uri(raw) {
+            Some((bucket, key)) => Sink::S3 { bucket, key },
+            None => Sink::Local(PathBuf::from(raw)),
+        }
+    }
+
+    /// Writes `bytes` to the destination, creating/overwriting it.
+    pub async fn write(&self, bytes: &[u8], config: &ObjectStorageConfig) -> Result<()> {
+        match self {
+            Sink::Local(path) => {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)
+                        .with_context(|| format!("Failed to create {}", parent.display()))?;
+                }
+                std::fs::write(path, bytes)
+                    .with_context(|| format!("Failed to write {}", path.display()))
+            }
+            Sink::S3 { bucket, key } => {
+                let client = s3_client(config).await?;
+                client
+                    .put_object()
+                    .bucket(bucket)
+                    .key(key)
+                    .body(bytes.to_vec().into())
+                    .send()
+                    .await
+                    .with_context(|| format!("Failed to put s3://{bucket}/{key}"))?;
+                Ok(())
+            }
+        }
+    }
+}
+
+async fn s3_client(config: &ObjectStorageConfig) -> Result<S3Client> {
+    let mut loader = aws_config::defaults(aws_config::BehaviorVersion::latest());
+    if let Some(region) = &config.region {
+        loader = loader.region(aws_config::Region::new(region.clone()));
+    }
+    if let Some(endpoint) = &config.endpoint {
+        loader = loader.endpoint_url(endpoint.clone());
+    }
+    Ok(S3Client::new(&loader.load().await))
+}
@@ -6,6 +6,8 @@ use serde_aux::field_attributes::deserialize_number_from_string;
 #[derive(Clone, Deserialize)]
 pub struct AppSettings {
     pub application: WebServerConfig,
+    #[serde(default)]
+    pub object_storage: ObjectStorageSettings,
 }
 
 #[derive(Clone, Deserialize)]
@@ -13,6 +15,39 @@ pub struct WebServerConfig {
     pub host: String,
     #[serde(deserialize_with = "deserialize_number_from_string")]
     pub port: u16,
+    /// Number of `HttpServer` worker threads. `None` (the default) lets
+    /// actix pick its own default (one per available core).
+    #[serde(default)]
+    pub workers: Option<usize>,
+    /// How long, after a shutdown signal, in-flight requests (including a
+    /// regeneration in progress) get to finish before the server forces the
+    /// connection closed.
+    #[serde(default = "default_shutdown_timeout_secs")]
+    pub shutdown_timeout_secs: u64,
+    /// Maximum size, in bytes, of a PDF accepted by `POST /v1/sanitise`.
+    /// Uploads larger than this are rejected with `413 Payload Too Large`
+    /// before any regeneration work begins.
+    #[serde(default = "default_max_upload_bytes")]
+    pub max_upload_bytes: usize,
+}
+
+fn default_shutdown_timeout_secs() -> u64 {
+    30
+}
+
+fn default_max_upload_bytes() -> usize {
+    1024 * 1024 * 100
+}
+
+/// Region/endpoint for the S3 client backing `s3://bucket/key` sources and
+/// sinks accepted by `POST /v1/sanitise/object`
+/// (see [`sanitiser::source::ObjectStorageConfig`]). Credentials themselves
+/// are resolved the standard AWS SDK way (environment, instance profile,
+/// ...) rather than kept here.
+#[derive(Clone, Deserialize, Default)]
+pub struct ObjectStorageSettings {
+    pub region: Option<String>,
+    pub endpoint: Option<String>,
 }
 
 pub fn get_app_settings() -> Result<AppSettings, config::ConfigError> {
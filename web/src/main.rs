@@ -1,8 +1,16 @@
-use web_server::{app_settings::get_app_settings, startup::Application};
+use app_settings::get_app_settings;
+use startup::Application;
+
+mod app_settings;
+mod metrics;
+mod routes;
+mod startup;
+mod telemetry;
 
 #[tokio::main]
 async fn main() -> Result<(), std::io::Error> {
     dotenv::dotenv().ok();
+    telemetry::init_tracing().expect("Could not set up tracing");
 
     let app_settings = get_app_settings().expect("Could not create AppSettings");
     let app = Application::build(app_settings).await?;
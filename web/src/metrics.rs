@@ -0,0 +1,72 @@
+use std::sync::OnceLock;
+
+use opentelemetry::metrics::{Counter, Histogram};
+use opentelemetry_sdk::metrics::SdkMeterProvider;
+use prometheus::{Registry, TextEncoder};
+
+/// Process-wide instrument handles, built once in [`init_meter_provider`] and
+/// shared (via `web::Data`) with every request handler instead of being
+/// re-created per call.
+pub struct AppMetrics {
+    pub sanitised_documents_total: Counter<u64>,
+    pub bytes_in_total: Counter<u64>,
+    pub bytes_out_total: Counter<u64>,
+    pub regeneration_duration_seconds: Histogram<f64>,
+}
+
+static PROMETHEUS_REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+/// Builds the OpenTelemetry meter provider backed by a Prometheus exporter,
+/// installs it as the process-global provider, and returns the instrument
+/// handles request handlers record against. The same `prometheus::Registry`
+/// backs [`render_prometheus_metrics`], which `GET /management/metrics`
+/// calls to expose them for scraping.
+pub fn init_meter_provider() -> AppMetrics {
+    let registry = Registry::new();
+    let exporter = opentelemetry_prometheus::exporter()
+        .with_registry(registry.clone())
+        .build()
+        .expect("Could not build Prometheus exporter");
+
+    let provider = SdkMeterProvider::builder().with_reader(exporter).build();
+    opentelemetry::global::set_meter_provider(provider);
+
+    PROMETHEUS_REGISTRY
+        .set(registry)
+        .expect("init_meter_provider called more than once");
+
+    let meter = opentelemetry::global::meter("sanitisium-web");
+
+    AppMetrics {
+        sanitised_documents_total: meter
+            .u64_counter("sanitised_documents_total")
+            .with_description("Number of PDFs successfully sanitised")
+            .build(),
+        bytes_in_total: meter
+            .u64_counter("bytes_in_total")
+            .with_description("Total bytes of uploaded PDFs received")
+            .build(),
+        bytes_out_total: meter
+            .u64_counter("bytes_out_total")
+            .with_description("Total bytes of sanitised PDFs returned")
+            .build(),
+        regeneration_duration_seconds: meter
+            .f64_histogram("regeneration_duration_seconds")
+            .with_description("Time spent regenerating a single PDF")
+            .build(),
+    }
+}
+
+/// Renders the process's metrics in Prometheus text exposition format, for
+/// `GET /management/metrics` to return directly.
+pub fn render_prometheus_metrics() -> String {
+    let registry = PROMETHEUS_REGISTRY
+        .get()
+        .expect("init_meter_provider must run before render_prometheus_metrics");
+
+    let mut buffer = String::new();
+    TextEncoder::new()
+        .encode_utf8(&registry.gather(), &mut buffer)
+        .expect("Could not encode metrics");
+    buffer
+}
@@ -0,0 +1,15 @@
+use actix_web::{HttpResponse, Responder};
+
+use crate::metrics;
+
+pub async fn health_check() -> impl Responder {
+    HttpResponse::Ok().body("Web server is up")
+}
+
+/// Exposes this process's metrics in Prometheus text exposition format for
+/// scraping.
+pub async fn metrics_handler() -> impl Responder {
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(metrics::render_prometheus_metrics())
+}
@@ -0,0 +1,175 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use actix_multipart::Multipart;
+use actix_web::{HttpResponse, Responder, web};
+use futures::StreamExt;
+use opentelemetry::KeyValue;
+use sanitiser::pdf::sanitise::regenerate_pdf_from_bytes;
+use sanitiser::source::{ObjectStorageConfig, Sink, Source};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use crate::app_settings::AppSettings;
+use crate::metrics::AppMetrics;
+
+/// Accepts a PDF as a `multipart/form-data` upload (field name `file`), runs
+/// it through `regenerate_pdf_from_bytes`, and streams the sanitised PDF back
+/// in the response body with `Content-Type: application/pdf`. The regenerate
+/// step is blocking (pdfium/lopdf), so it runs on `web::block`'s blocking
+/// thread pool rather than stalling the async runtime the rest of the
+/// server's connections share.
+#[instrument(skip(payload, settings, metrics))]
+pub async fn sanitise_pdf(
+    mut payload: Multipart,
+    settings: web::Data<AppSettings>,
+    metrics: web::Data<Arc<AppMetrics>>,
+) -> impl Responder {
+    let max_upload_bytes = settings.application.max_upload_bytes;
+    let mut file_bytes: Option<web::BytesMut> = None;
+
+    while let Some(field) = payload.next().await {
+        let mut field = match field {
+            Ok(field) => field,
+            Err(error) => {
+                return HttpResponse::BadRequest().body(format!("Invalid multipart body: {error}"));
+            }
+        };
+
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let mut buf = web::BytesMut::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(error) => {
+                    return HttpResponse::BadRequest().body(format!("Error reading upload: {error}"));
+                }
+            };
+
+            if buf.len() + chunk.len() > max_upload_bytes {
+                return HttpResponse::PayloadTooLarge()
+                    .body("Uploaded PDF exceeds the maximum allowed size");
+            }
+
+            buf.extend_from_slice(&chunk);
+        }
+
+        file_bytes = Some(buf);
+    }
+
+    let Some(file_bytes) = file_bytes else {
+        return HttpResponse::BadRequest().body("Missing `file` field in multipart upload");
+    };
+    let file_bytes = file_bytes.freeze();
+    metrics.bytes_in_total.add(file_bytes.len() as u64, &[]);
+
+    let start_time = Instant::now();
+    let regenerated = match web::block(move || regenerate_pdf_from_bytes(&file_bytes)).await {
+        Ok(Ok(bytes)) => bytes,
+        Ok(Err(error)) => {
+            tracing::error!("Failed to sanitise uploaded PDF. error={error}");
+            metrics
+                .regeneration_duration_seconds
+                .record(start_time.elapsed().as_secs_f64(), &[KeyValue::new("outcome", "error")]);
+            return HttpResponse::UnprocessableEntity().body("Could not sanitise the uploaded PDF");
+        }
+        Err(error) => {
+            tracing::error!("Sanitisation task panicked. error={error}");
+            return HttpResponse::InternalServerError().body("Sanitisation task failed unexpectedly");
+        }
+    };
+
+    metrics
+        .regeneration_duration_seconds
+        .record(start_time.elapsed().as_secs_f64(), &[KeyValue::new("outcome", "success")]);
+    metrics.sanitised_documents_total.add(1, &[]);
+    metrics.bytes_out_total.add(regenerated.len() as u64, &[]);
+
+    HttpResponse::Ok()
+        .content_type("application/pdf")
+        .body(regenerated)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SanitiseObjectQuery {
+    /// `s3://bucket/key` URI to read the input PDF from.
+    pub source: String,
+    /// `s3://bucket/key` URI to write the sanitised PDF to.
+    pub sink: String,
+}
+
+#[derive(Serialize)]
+struct SanitiseObjectResponse<'a> {
+    sink: &'a str,
+}
+
+/// Same as [`sanitise_pdf`], but reads the input and writes the output
+/// through a [`Source`]/[`Sink`] instead of the request/response bodies, so
+/// a caller can sanitise a PDF straight from one S3 bucket/key to another
+/// without sending it in the request or receiving it in the response; this
+/// service still downloads, regenerates and uploads the bytes itself.
+///
+/// `source`/`sink` are restricted to `s3://bucket/key` URIs: this route is
+/// unauthenticated, and `Source`/`Sink` otherwise fall back to treating
+/// anything else as a local filesystem path, which would turn this endpoint
+/// into an arbitrary file read/write primitive against this host.
+#[instrument(skip(settings, metrics))]
+pub async fn sanitise_object(
+    query: web::Query<SanitiseObjectQuery>,
+    settings: web::Data<AppSettings>,
+    metrics: web::Data<Arc<AppMetrics>>,
+) -> impl Responder {
+    if !query.source.starts_with("s3://") || !query.sink.starts_with("s3://") {
+        return HttpResponse::BadRequest().body("`source` and `sink` must be s3:// URIs");
+    }
+
+    let storage = ObjectStorageConfig {
+        region: settings.object_storage.region.clone(),
+        endpoint: settings.object_storage.endpoint.clone(),
+    };
+    let source = Source::parse(&query.source);
+    let sink = Sink::parse(&query.sink);
+
+    let input_bytes = match source.read(&storage).await {
+        Ok(bytes) => bytes,
+        Err(error) => {
+            tracing::error!("Failed to read source {}. error={error}", query.source);
+            return HttpResponse::BadRequest().body("Could not read the source object");
+        }
+    };
+    metrics.bytes_in_total.add(input_bytes.len() as u64, &[]);
+
+    let start_time = Instant::now();
+    let output_bytes = match web::block(move || regenerate_pdf_from_bytes(&input_bytes)).await {
+        Ok(Ok(bytes)) => bytes,
+        Ok(Err(error)) => {
+            tracing::error!("Failed to sanitise source object. error={error}");
+            metrics.regeneration_duration_seconds.record(
+                start_time.elapsed().as_secs_f64(),
+                &[KeyValue::new("outcome", "error")],
+            );
+            return HttpResponse::UnprocessableEntity().body("Could not sanitise the source object");
+        }
+        Err(error) => {
+            tracing::error!("Sanitisation task panicked. error={error}");
+            return HttpResponse::InternalServerError().body("Sanitisation task failed unexpectedly");
+        }
+    };
+    metrics.regeneration_duration_seconds.record(
+        start_time.elapsed().as_secs_f64(),
+        &[KeyValue::new("outcome", "success")],
+    );
+
+    if let Err(error) = sink.write(&output_bytes, &storage).await {
+        tracing::error!("Failed to write sink {}. error={error}", query.sink);
+        return HttpResponse::InternalServerError().body("Could not write the sanitised output");
+    }
+
+    metrics.sanitised_documents_total.add(1, &[]);
+    metrics.bytes_out_total.add(output_bytes.len() as u64, &[]);
+
+    HttpResponse::Ok().json(SanitiseObjectResponse { sink: &query.sink })
+}
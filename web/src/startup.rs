@@ -1,8 +1,17 @@
 use std::net::TcpListener;
+use std::sync::Arc;
 
 use actix_web::{App, HttpServer, dev::Server, web};
+use actix_web_opentelemetry::RequestTracing;
 
-use crate::{app_settings::AppSettings, routes::health::health_check};
+use crate::{
+    app_settings::AppSettings,
+    metrics,
+    routes::{
+        health::{health_check, metrics_handler},
+        sanitise::{sanitise_object, sanitise_pdf},
+    },
+};
 
 pub struct Application {
     port: u16,
@@ -25,7 +34,10 @@ impl Application {
         self.port
     }
 
-    /// Run the web server and blocks the main thread until it stops
+    /// Runs the web server and blocks until it stops. A SIGINT/SIGTERM
+    /// (or Ctrl-C) triggers actix's own graceful shutdown: no new
+    /// connections are accepted, and in-flight requests get up to
+    /// `application.shutdown_timeout_secs` to finish before this returns.
     pub async fn run_until_stopped(self) -> Result<(), std::io::Error> {
         println!("Server started on port {}", &self.port);
         self.server.await
@@ -37,15 +49,32 @@ fn run(listener: TcpListener, settings: AppSettings) -> Result<Server, std::io::
         .local_addr()
         .expect("TCPListener is invalid")
         .port();
+    let shutdown_timeout_secs = settings.application.shutdown_timeout_secs;
+    let workers = settings.application.workers;
     let settings = web::Data::new(settings);
+    let metrics = web::Data::new(Arc::new(metrics::init_meter_provider()));
 
-    let server = HttpServer::new(move || {
+    let mut http_server = HttpServer::new(move || {
         App::new()
+            .wrap(RequestTracing::default())
             .route("/management/health", web::get().to(health_check))
+            .route("/management/metrics", web::get().to(metrics_handler))
+            .route("/v1/sanitise", web::post().to(sanitise_pdf))
+            .route("/v1/sanitise/object", web::post().to(sanitise_object))
             .app_data(settings.clone())
+            .app_data(metrics.clone())
     })
-    .listen(listener)?
-    .run();
+    // actix-web's `Server` already installs SIGINT/SIGTERM handlers and, on
+    // receipt, stops accepting new connections while giving in-flight
+    // requests (a regeneration in progress included) up to this long to
+    // finish before forcing them closed.
+    .shutdown_timeout(shutdown_timeout_secs);
+
+    if let Some(workers) = workers {
+        http_server = http_server.workers(workers);
+    }
+
+    let server = http_server.listen(listener)?.run();
 
     println!("Sanitisium Web Server is running on port {port}");
 
@@ -0,0 +1,63 @@
+use std::env;
+
+use opentelemetry_otlp::{SpanExporter, WithExportConfig, WithHttpConfig};
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::runtime;
+use opentelemetry_sdk::trace::span_processor_with_async_runtime;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
+
+/// Whether the OTLP trace exporter should be wired in. Off by default (a
+/// plain fmt subscriber is enough for local development); set
+/// `OTEL_EXPORTER_OTLP_ENDPOINT` to turn it on and point it at a collector.
+fn otlp_enabled() -> bool {
+    env::var("OTEL_EXPORTER_OTLP_ENDPOINT").is_ok()
+}
+
+/// Sets up the global tracing subscriber: a plain fmt layer always, plus an
+/// OpenTelemetry OTLP layer when [`otlp_enabled`] — so spans from request
+/// handling (via `actix_web_opentelemetry::RequestTracing`, wired in
+/// `startup::run`) and the PDF regeneration pipeline are exported to a
+/// collector in production without needing it for local development.
+pub fn init_tracing() -> anyhow::Result<()> {
+    let filter_layer = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let fmt_layer = tracing_subscriber::fmt::layer().with_target(false);
+
+    if !otlp_enabled() {
+        tracing_subscriber::registry()
+            .with(filter_layer)
+            .with(fmt_layer)
+            .init();
+        return Ok(());
+    }
+
+    let span_exporter = SpanExporter::builder()
+        .with_http()
+        .with_http_client(reqwest::Client::new())
+        .build()?;
+
+    let batch_processor = span_processor_with_async_runtime::BatchSpanProcessor::builder(
+        span_exporter,
+        runtime::Tokio,
+    )
+    .build();
+
+    let tracer = opentelemetry_sdk::trace::SdkTracerProvider::builder()
+        .with_span_processor(batch_processor)
+        .with_resource(
+            Resource::builder()
+                .with_service_name("sanitisium-web")
+                .build(),
+        )
+        .build()
+        .tracer("sanitisium-web");
+
+    tracing_subscriber::registry()
+        .with(filter_layer)
+        .with(fmt_layer)
+        .with(OpenTelemetryLayer::new(tracer))
+        .init();
+
+    Ok(())
+}
+
@@ -7,12 +7,170 @@ use serde_aux::field_attributes::deserialize_number_from_string;
 pub struct AppSettings {
     pub application: WebServerConfig,
     pub sanitisation: SanitisationConfig,
+    #[serde(default)]
+    pub job_backend: JobBackendConfig,
+    #[serde(default)]
+    pub storage: StorageConfig,
+}
+
+/// Selects where [`crate::storage::StorageBackend`] persists input/output
+/// files. `Local` (the default) writes straight to `sanitisation.pdfs_dir`
+/// on this node's disk. `S3` additionally durable-syncs every write to an
+/// S3-compatible bucket, so sanitised output and queued input survive a pod
+/// restart and are visible to every replica rather than just the one that
+/// processed the job; `sanitisation.pdfs_dir` is still used underneath as a
+/// local staging directory, since PDFium/`procspawn` only know how to read
+/// and write real files.
+#[derive(Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StorageConfig {
+    Local,
+    S3 {
+        bucket: String,
+        region: String,
+        #[serde(default)]
+        endpoint: Option<String>,
+    },
+    /// Same on-disk layout as `Local`, but reads and writes go through
+    /// io_uring. Only buildable on Linux with the `io_uring` cargo feature
+    /// enabled — see `storage::uring`.
+    #[cfg(all(target_os = "linux", feature = "io_uring"))]
+    Uring,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        StorageConfig::Local
+    }
+}
+
+/// Selects which `apalis` backend the job scheduler persists queued and
+/// in-flight jobs to. `Memory` is the default for local development; jobs
+/// enqueued against it do not survive a restart. `Postgres` should be used
+/// in production so a crash between HTTP accept and processing doesn't
+/// silently drop the uploaded file.
+#[derive(Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JobBackendConfig {
+    Memory,
+    Postgres { database_url: String },
+}
+
+impl Default for JobBackendConfig {
+    fn default() -> Self {
+        JobBackendConfig::Memory
+    }
 }
 
 #[derive(Clone, Deserialize)]
 pub struct SanitisationConfig {
     /// Base directory to store PDF files for processing sanitisation requests
     pub pdfs_dir: String,
+    /// Maximum number of PDFs that may be rasterised/merged at the same time.
+    /// Defaults to the number of available CPU cores so we don't oversubscribe
+    /// memory on the host running pdfium.
+    #[serde(default = "default_max_concurrent_jobs")]
+    pub max_concurrent_jobs: usize,
+    /// Maximum number of attempts for a retryable failure — both chunk
+    /// regeneration and success/failure callback delivery — before the
+    /// chunk/callback is given up on. For regeneration, exhausting retries
+    /// moves the job to the dead-letter directory. `1` means no retries.
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Base delay, in milliseconds, for the exponential backoff applied
+    /// between retries of either regeneration or callback delivery. Doubles
+    /// on each attempt, capped at 30s, plus a small jitter to avoid retry
+    /// storms.
+    #[serde(default = "default_retry_base_delay_ms")]
+    pub retry_base_delay_ms: u64,
+    /// Number of pages per chunk when splitting a job's input PDF for
+    /// parallel sanitisation across the `procspawn` pool. Mirrors the
+    /// batch size `regenerate_pdf` itself processes pages in.
+    #[serde(default = "default_chunk_page_size")]
+    pub chunk_page_size: u16,
+    /// Maximum number of page chunks of a single job that may be regenerating
+    /// at once. The bound is per job: each job in flight (up to
+    /// `max_concurrent_jobs`) gets its own `chunk_parallelism` slots, so this
+    /// does not cap the process-wide total. Defaults to the number of
+    /// available CPU cores; low-memory hosts can set it lower to bound how
+    /// many `procspawn` children each job runs concurrently.
+    #[serde(default = "default_max_concurrent_jobs")]
+    pub chunk_parallelism: usize,
+    /// Render DPI passed through to `regenerate_pdf_with_options`. Higher
+    /// values produce sharper but larger pages.
+    #[serde(default = "default_render_dpi")]
+    pub render_dpi: f32,
+    /// Output image quality (0-100) passed through to
+    /// `regenerate_pdf_with_options`.
+    #[serde(default = "default_render_jpg_quality")]
+    pub render_jpg_quality: f32,
+    /// How many pages `regenerate_pdf_with_options` rasterises and assembles
+    /// per intermediate PDF before merging, bounding peak memory per chunk.
+    /// Distinct from `chunk_page_size`, which bounds how many pages go into
+    /// one `procspawn` child in the first place.
+    #[serde(default = "default_render_page_batch")]
+    pub render_page_batch: u16,
+}
+
+fn default_max_concurrent_jobs() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+fn default_retry_base_delay_ms() -> u64 {
+    500
+}
+
+fn default_chunk_page_size() -> u16 {
+    5
+}
+
+fn default_render_dpi() -> f32 {
+    300.0
+}
+
+fn default_render_jpg_quality() -> f32 {
+    70.0
+}
+
+fn default_render_page_batch() -> u16 {
+    5
+}
+
+impl SanitisationConfig {
+    /// Rejects nonsensical configs: a `render_page_batch`/`chunk_page_size`
+    /// of `0`, which the renderer and splitter would otherwise quietly treat
+    /// as `1`, and a `render_dpi`/`render_jpg_quality` of `0`, which renders
+    /// every page blank or empty. Called once at startup so a typo'd config
+    /// or env override fails fast instead of being silently reinterpreted.
+    fn validate(&self) -> Result<(), config::ConfigError> {
+        if self.render_page_batch == 0 {
+            return Err(config::ConfigError::Message(
+                "sanitisation.render_page_batch must be greater than 0".into(),
+            ));
+        }
+        if self.chunk_page_size == 0 {
+            return Err(config::ConfigError::Message(
+                "sanitisation.chunk_page_size must be greater than 0".into(),
+            ));
+        }
+        if self.render_dpi <= 0.0 {
+            return Err(config::ConfigError::Message(
+                "sanitisation.render_dpi must be greater than 0".into(),
+            ));
+        }
+        if self.render_jpg_quality <= 0.0 {
+            return Err(config::ConfigError::Message(
+                "sanitisation.render_jpg_quality must be greater than 0".into(),
+            ));
+        }
+        Ok(())
+    }
 }
 
 #[derive(Clone, Deserialize)]
@@ -64,5 +222,7 @@ pub fn get_app_settings() -> Result<AppSettings, config::ConfigError> {
         )
         .build()?;
 
-    builder.try_deserialize::<AppSettings>()
+    let settings = builder.try_deserialize::<AppSettings>()?;
+    settings.sanitisation.validate()?;
+    Ok(settings)
 }
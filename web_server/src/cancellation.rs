@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+/// Registry of per-job cancellation tokens, shared between the scheduler
+/// (which signals cancellation from `DELETE /jobs/{id}`) and the worker
+/// (which checks the token between chunk dispatches). Mirrors
+/// [`crate::reports::JobReportStore`]'s shape — an `Arc<Mutex<HashMap<...>>>`
+/// cloned into both `SanitisePdfScheduler` and `WorkerData`.
+#[derive(Debug, Default, Clone)]
+pub struct CancellationRegistry {
+    tokens: Arc<Mutex<HashMap<String, CancellationToken>>>,
+}
+
+impl CancellationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a fresh token for a newly-enqueued job.
+    pub async fn register(&self, job_id: &str) {
+        self.tokens
+            .lock()
+            .await
+            .insert(job_id.to_string(), CancellationToken::new());
+    }
+
+    /// Signal cancellation for a job, if it is still tracked. Returns `true`
+    /// if a token was found and cancelled.
+    pub async fn cancel(&self, job_id: &str) -> bool {
+        match self.tokens.lock().await.get(job_id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Look up the token for a job, if still tracked.
+    pub async fn get(&self, job_id: &str) -> Option<CancellationToken> {
+        self.tokens.lock().await.get(job_id).cloned()
+    }
+
+    /// Stop tracking a job's token once it's done (completed, failed, or
+    /// cancelled), so the registry doesn't grow unboundedly.
+    pub async fn remove(&self, job_id: &str) {
+        self.tokens.lock().await.remove(job_id);
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use sqlx::{PgPool, Row};
+use tokio::sync::Mutex;
+
+/// Maps a content hash (hex SHA-256 of an uploaded PDF's bytes) to the
+/// filename of its most recent successful sanitisation output, so an
+/// identical re-upload can be served from cache instead of re-running
+/// PDFium. Mirrors [`crate::reports::JobReportStore`]'s `Memory`/`Postgres`
+/// split: `Memory` is the default for local development and does not
+/// survive a restart — a fresh process re-sanitises duplicates until its
+/// cache warms back up. `Postgres` persists the index in the same database
+/// as the durable queue, in a `dedupe_index` table, so the cache (and the
+/// CPU it saves on duplicate submissions) survives a restart too.
+#[derive(Debug, Clone)]
+pub enum DedupeIndex {
+    Memory(Arc<Mutex<HashMap<String, String>>>),
+    Postgres(PgPool),
+}
+
+impl Default for DedupeIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DedupeIndex {
+    pub fn new() -> Self {
+        DedupeIndex::Memory(Arc::new(Mutex::new(HashMap::new())))
+    }
+
+    /// Backs the index with `pool` — the same pool
+    /// [`crate::workers::job::JobQueueStorage::Postgres`] persists the queue
+    /// to — creating the backing table if it doesn't already exist.
+    pub async fn new_postgres(pool: PgPool) -> Result<Self, sqlx::Error> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS dedupe_index (
+                content_hash TEXT PRIMARY KEY,
+                output_filename TEXT NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(DedupeIndex::Postgres(pool))
+    }
+
+    /// Look up the cached output filename for a content hash, if a prior
+    /// sanitisation of identical bytes has already completed.
+    pub async fn get(&self, content_hash: &str) -> Option<String> {
+        match self {
+            DedupeIndex::Memory(outputs) => outputs.lock().await.get(content_hash).cloned(),
+            DedupeIndex::Postgres(pool) => {
+                let row = sqlx::query(
+                    "SELECT output_filename FROM dedupe_index WHERE content_hash = $1",
+                )
+                .bind(content_hash)
+                .fetch_optional(pool)
+                .await
+                .inspect_err(|error| {
+                    tracing::error!(
+                        "Failed to look up dedupe entry. content_hash={content_hash} error={error}"
+                    )
+                })
+                .ok()??;
+                row.try_get("output_filename").ok()
+            }
+        }
+    }
+
+    /// Record that `content_hash` sanitised to `output_filename`, so the
+    /// next identical upload can be served from cache.
+    pub async fn insert(&self, content_hash: String, output_filename: String) {
+        match self {
+            DedupeIndex::Memory(outputs) => {
+                outputs.lock().await.insert(content_hash, output_filename);
+            }
+            DedupeIndex::Postgres(pool) => {
+                let result = sqlx::query(
+                    "INSERT INTO dedupe_index (content_hash, output_filename)
+                     VALUES ($1, $2)
+                     ON CONFLICT (content_hash) DO UPDATE SET output_filename = EXCLUDED.output_filename",
+                )
+                .bind(&content_hash)
+                .bind(&output_filename)
+                .execute(pool)
+                .await;
+
+                if let Err(error) = result {
+                    tracing::error!(
+                        "Failed to persist dedupe entry. content_hash={content_hash} error={error}"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -9,10 +9,10 @@ use web_server::{
 async fn main() -> Result<()> {
     dotenv::dotenv().ok();
 
-    // Init telemetry subscriber to process tracing spans and logs
-    // TODO: Read these values from environment variables instead.
-    // Leverage Otel default environment variables as much as possible later:
-    // See: https://opentelemetry.io/docs/specs/otel/configuration/sdk-environment-variables/#general-sdk-configuration
+    // Init telemetry subscriber to process tracing spans and logs. These are
+    // just the defaults — standard OTEL_* environment variables (service
+    // name, sampler, exporter endpoint/protocol, metric export interval) take
+    // priority when set; see `telemetry::get_telemetry_subscriber`.
     let subscriber =
         get_telemetry_subscriber("sanitisium", "alpha", "dev", "info", std::io::stdout);
     init_telemetry_subscriber(subscriber);
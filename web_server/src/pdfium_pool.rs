@@ -0,0 +1,114 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use pdfium_render::prelude::Pdfium;
+use sanitiser::pdf::load_pdfium::get_pdfium_instance;
+use tokio::sync::{Mutex, OwnedSemaphorePermit, Semaphore};
+
+/// A fixed-size pool of pre-bound `Pdfium` instances.
+///
+/// Binding to the pdfium dynamic library is comparatively expensive, so
+/// rather than paying that cost on every sanitisation call, we keep a small
+/// number of instances around and hand them out to callers on checkout.
+/// Instances are created lazily, up to `max_size`, and returned to the pool
+/// when the guard is dropped.
+pub struct PdfiumPool {
+    max_size: usize,
+    permits: Arc<Semaphore>,
+    idle: Mutex<Vec<Pdfium>>,
+    /// Set once the first `Pdfium` instance has been bound successfully.
+    /// Readiness checks use this to report whether the server is actually
+    /// able to process PDFs, rather than just that it has started.
+    has_bound: AtomicBool,
+}
+
+impl PdfiumPool {
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            max_size,
+            permits: Arc::new(Semaphore::new(max_size)),
+            idle: Mutex::new(Vec::with_capacity(max_size)),
+            has_bound: AtomicBool::new(false),
+        }
+    }
+
+    /// Check out a `Pdfium` instance, waiting for one to become available if
+    /// every instance is currently in use. Binding a fresh instance is an
+    /// expensive, blocking dynamic-library call, so it runs inside
+    /// `spawn_blocking` rather than directly on the caller's async task.
+    pub async fn acquire(&self) -> PdfiumGuard<'_> {
+        let permit = self
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("PdfiumPool semaphore should never be closed");
+
+        let pooled = {
+            let mut idle = self.idle.lock().await;
+            idle.pop()
+        };
+
+        let instance = match pooled {
+            Some(instance) => instance,
+            None => tokio::task::spawn_blocking(get_pdfium_instance)
+                .await
+                .expect("binding Pdfium panicked"),
+        };
+        self.has_bound.store(true, Ordering::Relaxed);
+
+        PdfiumGuard {
+            pool: self,
+            permit: Some(permit),
+            instance: Some(instance),
+        }
+    }
+
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// Whether a `Pdfium` instance has been bound successfully at least once.
+    pub fn has_bound(&self) -> bool {
+        self.has_bound.load(Ordering::Relaxed)
+    }
+}
+
+/// A checked-out `Pdfium` instance. Returns itself to the pool on drop so
+/// the next caller can reuse it instead of re-binding the library.
+pub struct PdfiumGuard<'a> {
+    pool: &'a PdfiumPool,
+    permit: Option<OwnedSemaphorePermit>,
+    instance: Option<Pdfium>,
+}
+
+impl Deref for PdfiumGuard<'_> {
+    type Target = Pdfium;
+
+    fn deref(&self) -> &Self::Target {
+        self.instance.as_ref().expect("instance taken before drop")
+    }
+}
+
+impl DerefMut for PdfiumGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.instance.as_mut().expect("instance taken before drop")
+    }
+}
+
+impl Drop for PdfiumGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(instance) = self.instance.take() {
+            // `try_lock` is safe here: the mutex is only ever held briefly
+            // inside `acquire`/`drop`, so contention is not expected, and
+            // falling back to dropping the instance instead of blocking in
+            // `Drop` is preferable either way.
+            if let Ok(mut idle) = self.pool.idle.try_lock() {
+                idle.push(instance);
+            }
+        }
+        // Dropping the permit releases the slot back to the semaphore.
+        self.permit.take();
+    }
+}
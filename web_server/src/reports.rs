@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use tokio::sync::Mutex;
+
+/// Lifecycle state of a sanitisation job, as tracked by a [`JobReport`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case", tag = "state")]
+pub enum JobState {
+    Queued,
+    Processing,
+    Completed,
+    Failed { error: String },
+    Cancelled,
+}
+
+/// A point-in-time record of a submitted sanitisation job, queryable via
+/// `GET /jobs/{id}` so callers can poll instead of relying solely on the
+/// success/failure callback.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobReport {
+    pub id: String,
+    pub state: JobState,
+    /// Total page batches the input was split into, once known.
+    /// `regenerate_pdf` doesn't report incremental progress back across the
+    /// procspawn boundary yet, so this stays `None`/`0` for now.
+    pub pages_total: Option<u32>,
+    pub pages_done: u32,
+    /// Filename of the sanitised output under the storage base dir, set
+    /// once the job has completed.
+    pub output_filename: Option<String>,
+    pub queued_at: u64,
+    pub started_at: Option<u64>,
+    pub finished_at: Option<u64>,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl JobReport {
+    fn queued(id: String) -> Self {
+        Self {
+            id,
+            state: JobState::Queued,
+            pages_total: None,
+            pages_done: 0,
+            output_filename: None,
+            queued_at: now(),
+            started_at: None,
+            finished_at: None,
+        }
+    }
+}
+
+/// Store of job reports, keyed by job id. `Memory` is an in-process map — the
+/// default for local development — and does not survive a restart.
+/// `Postgres` persists reports in the same database as the durable queue
+/// (see [`crate::app_settings::JobBackendConfig::Postgres`]), in a
+/// `job_reports` table, so `GET /jobs/{id}` keeps working across a restart
+/// the way the queue itself does. Reports are still best-effort status for
+/// polling callers; the success/failure callback remains the durable
+/// notification path.
+#[derive(Debug, Clone)]
+pub enum JobReportStore {
+    Memory(Arc<Mutex<HashMap<String, JobReport>>>),
+    Postgres(PgPool),
+}
+
+impl Default for JobReportStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JobReportStore {
+    pub fn new() -> Self {
+        JobReportStore::Memory(Arc::new(Mutex::new(HashMap::new())))
+    }
+
+    /// Backs reports with `pool` — the same pool [`crate::workers::job::JobQueueStorage::Postgres`]
+    /// persists the queue to — creating the backing table if it doesn't
+    /// already exist.
+    pub async fn new_postgres(pool: PgPool) -> Result<Self, sqlx::Error> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS job_reports (
+                id TEXT PRIMARY KEY,
+                state_json TEXT NOT NULL,
+                pages_total INTEGER,
+                pages_done INTEGER NOT NULL,
+                output_filename TEXT,
+                queued_at BIGINT NOT NULL,
+                started_at BIGINT,
+                finished_at BIGINT
+            )",
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(JobReportStore::Postgres(pool))
+    }
+
+    pub async fn mark_queued(&self, id: &str) {
+        let report = JobReport::queued(id.to_string());
+        match self {
+            JobReportStore::Memory(reports) => {
+                reports.lock().await.insert(id.to_string(), report);
+            }
+            JobReportStore::Postgres(pool) => {
+                if let Err(error) = Self::upsert(pool, &report).await {
+                    tracing::error!("Failed to persist queued job report. id={id} error={error}");
+                }
+            }
+        }
+    }
+
+    pub async fn mark_processing(&self, id: &str) {
+        self.update(id, |report| {
+            report.state = JobState::Processing;
+            report.started_at = Some(now());
+        })
+        .await;
+    }
+
+    pub async fn mark_completed(&self, id: &str, output_filename: String) {
+        self.update(id, |report| {
+            report.state = JobState::Completed;
+            report.output_filename = Some(output_filename);
+            report.finished_at = Some(now());
+        })
+        .await;
+    }
+
+    pub async fn mark_failed(&self, id: &str, error: String) {
+        self.update(id, |report| {
+            report.state = JobState::Failed { error };
+            report.finished_at = Some(now());
+        })
+        .await;
+    }
+
+    pub async fn mark_cancelled(&self, id: &str) {
+        self.update(id, |report| {
+            report.state = JobState::Cancelled;
+            report.finished_at = Some(now());
+        })
+        .await;
+    }
+
+    pub async fn get(&self, id: &str) -> Option<JobReport> {
+        match self {
+            JobReportStore::Memory(reports) => reports.lock().await.get(id).cloned(),
+            JobReportStore::Postgres(pool) => Self::load(pool, id).await,
+        }
+    }
+
+    /// Shared core of the `mark_*` methods: load the existing report (a
+    /// no-op if it's missing, mirroring the `Memory` variant's
+    /// `get_mut(id)`), apply `mutate`, then write it back.
+    async fn update(&self, id: &str, mutate: impl FnOnce(&mut JobReport)) {
+        match self {
+            JobReportStore::Memory(reports) => {
+                let mut reports = reports.lock().await;
+                if let Some(report) = reports.get_mut(id) {
+                    mutate(report);
+                }
+            }
+            JobReportStore::Postgres(pool) => {
+                let Some(mut report) = Self::load(pool, id).await else {
+                    return;
+                };
+                mutate(&mut report);
+                if let Err(error) = Self::upsert(pool, &report).await {
+                    tracing::error!("Failed to persist job report update. id={id} error={error}");
+                }
+            }
+        }
+    }
+
+    async fn load(pool: &PgPool, id: &str) -> Option<JobReport> {
+        let row = sqlx::query(
+            "SELECT id, state_json, pages_total, pages_done, output_filename, queued_at, started_at, finished_at
+             FROM job_reports WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .inspect_err(|error| tracing::error!("Failed to load job report. id={id} error={error}"))
+        .ok()??;
+
+        let state_json: String = row.try_get("state_json").ok()?;
+        let state: JobState = serde_json::from_str(&state_json).ok()?;
+
+        Some(JobReport {
+            id: row.try_get("id").ok()?,
+            state,
+            pages_total: row
+                .try_get::<Option<i32>, _>("pages_total")
+                .ok()?
+                .map(|n| n as u32),
+            pages_done: row.try_get::<i32, _>("pages_done").ok()? as u32,
+            output_filename: row.try_get("output_filename").ok()?,
+            queued_at: row.try_get::<i64, _>("queued_at").ok()? as u64,
+            started_at: row
+                .try_get::<Option<i64>, _>("started_at")
+                .ok()?
+                .map(|n| n as u64),
+            finished_at: row
+                .try_get::<Option<i64>, _>("finished_at")
+                .ok()?
+                .map(|n| n as u64),
+        })
+    }
+
+    async fn upsert(pool: &PgPool, report: &JobReport) -> Result<(), sqlx::Error> {
+        let state_json = serde_json::to_string(&report.state)
+            .map_err(|error| sqlx::Error::Encode(Box::new(error)))?;
+
+        sqlx::query(
+            "INSERT INTO job_reports
+                (id, state_json, pages_total, pages_done, output_filename, queued_at, started_at, finished_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+             ON CONFLICT (id) DO UPDATE SET
+                state_json = EXCLUDED.state_json,
+                pages_total = EXCLUDED.pages_total,
+                pages_done = EXCLUDED.pages_done,
+                output_filename = EXCLUDED.output_filename,
+                started_at = EXCLUDED.started_at,
+                finished_at = EXCLUDED.finished_at",
+        )
+        .bind(&report.id)
+        .bind(state_json)
+        .bind(report.pages_total.map(|n| n as i32))
+        .bind(report.pages_done as i32)
+        .bind(&report.output_filename)
+        .bind(report.queued_at as i64)
+        .bind(report.started_at.map(|n| n as i64))
+        .bind(report.finished_at.map(|n| n as i64))
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+use actix_web::{web, HttpResponse, Responder};
+use futures::FutureExt;
+use serde::Serialize;
+use tracing::instrument;
+
+use crate::startup::{AppServices, MAX_PAYLOAD_SIZE};
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+
+#[derive(Serialize)]
+struct Capabilities {
+    pdfium_arch: &'static str,
+    pdfium_bound: bool,
+    max_payload_bytes: usize,
+    worker_concurrency: usize,
+    queue_depth: usize,
+}
+
+/// Architecture identifier for the PDFium binary this build would try to
+/// bind to, mirroring `sanitiser::pdf::load_pdfium::get_pdfium_instance`.
+fn pdfium_arch() -> &'static str {
+    if cfg!(target_os = "macos") {
+        return "macos-arm";
+    }
+    if cfg!(target_os = "linux") && cfg!(target_arch = "aarch64") {
+        return "linux-arm64";
+    }
+    "linux-amd64"
+}
+
+#[instrument(skip(services))]
+pub async fn capabilities(services: web::Data<Arc<AppServices>>) -> impl Responder {
+    let body = Capabilities {
+        pdfium_arch: pdfium_arch(),
+        pdfium_bound: services.pdfium_pool.has_bound(),
+        max_payload_bytes: MAX_PAYLOAD_SIZE,
+        worker_concurrency: services.pdfium_pool.max_size(),
+        queue_depth: services.job_scheduler.queue_depth(),
+    };
+
+    HttpResponse::Ok().json(body)
+}
+
+/// Reports whether the server is actually able to process PDFs, as opposed
+/// to merely having started. Exercises the pool on every call (cheap once
+/// bound, since the instance is just checked out and returned), so a host
+/// where the PDFium binary fails to bind keeps reporting `503` instead of
+/// going green the moment the process starts.
+#[instrument(skip(services))]
+pub async fn ready(services: web::Data<Arc<AppServices>>) -> impl Responder {
+    // A failed bind panics inside `acquire`; catch it so it surfaces as 503.
+    let _ = AssertUnwindSafe(services.pdfium_pool.acquire())
+        .catch_unwind()
+        .await;
+
+    if services.pdfium_pool.has_bound() {
+        HttpResponse::Ok().body("Ready")
+    } else {
+        HttpResponse::ServiceUnavailable().body("PDFium has not bound yet")
+    }
+}
@@ -0,0 +1,3 @@
+pub mod capabilities;
+pub mod health;
+pub mod sanitise;
@@ -1,19 +1,37 @@
 use std::sync::Arc;
 
+use actix_files::NamedFile;
 use actix_web::{
     HttpRequest, HttpResponse, Responder,
-    web::{self, Bytes, Query},
+    web::{self, Bytes, Path, Query},
 };
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use tracing::instrument;
 
-use crate::{startup::AppServices, workers::job::SanitisePDFRequest};
+use crate::{
+    reports::JobState,
+    startup::AppServices,
+    workers::job::{SanitisationMode, SanitisePDFRequest},
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SanitisePDFRequestArgs {
     pub id: String,
     pub success_callback_url: String,
     pub failure_callback_url: String,
+    /// Sanitisation strategy: `raster` (default) rasterises every page,
+    /// destroying native text but guaranteeing no PDF object survives;
+    /// `structural` keeps text/fonts/vectors intact and only strips
+    /// active-content constructs (see
+    /// `sanitiser::pdf::structural::structural_clean`).
+    #[serde(default)]
+    pub mode: SanitisationMode,
+}
+
+#[derive(Debug, Serialize)]
+struct EnqueueResponse<'a> {
+    job_id: &'a str,
 }
 
 #[instrument(skip(_req, body, services))]
@@ -23,27 +41,127 @@ pub async fn enqueue_pdf(
     query: Query<SanitisePDFRequestArgs>,
     services: web::Data<Arc<AppServices>>,
 ) -> impl Responder {
-    let filename = format!("{}.pdf", uuid::Uuid::new_v4());
-    if let Err(error) = &services.file_storage.store_file(&filename, &body) {
-        tracing::info!("Could not store PDF file. filename={filename} error={error}");
-        return HttpResponse::BadRequest().body("Error while storing PDF file");
-    }
+    // Keyed by content hash (not a random id) so identical uploads map to
+    // the same stored filename and can be recognised as duplicates.
+    let content_hash = format!("{:x}", Sha256::digest(&body));
+    let filename = format!("{content_hash}.pdf");
 
     let request_args = query.into_inner();
-    match services
+    let job_id = request_args.id.clone();
+    let request = SanitisePDFRequest::new(
+        filename.clone(),
+        request_args.id,
+        request_args.success_callback_url,
+        request_args.failure_callback_url,
+        request_args.mode,
+    );
+
+    if services
         .job_scheduler
-        .enqueue(SanitisePDFRequest::new(
-            filename,
-            request_args.id,
-            request_args.success_callback_url,
-            request_args.failure_callback_url,
-        ))
+        .try_serve_cached(&content_hash, &request, &services.file_storage)
         .await
     {
-        Ok(_) => HttpResponse::Ok().body("PDF added to queue for processing"),
+        return HttpResponse::Accepted().json(EnqueueResponse { job_id: &job_id });
+    }
+
+    if let Err(error) = services.file_storage.store_file(&filename, &body).await {
+        tracing::info!("Could not store PDF file. filename={filename} error={error}");
+        return HttpResponse::BadRequest().body("Error while storing PDF file");
+    }
+
+    match services.job_scheduler.enqueue(request).await {
+        Ok(_) => HttpResponse::Accepted().json(EnqueueResponse { job_id: &job_id }),
         Err(e) => {
             tracing::error!("Could not enqueue PDF job. error={e}");
             HttpResponse::BadRequest().body("Error scheduling PDF to be processed")
         }
     }
 }
+
+/// Reports the status of a previously submitted job. Once the job has
+/// completed, streams the sanitised PDF back instead of a JSON body, so
+/// callers that prefer polling over a success callback can fetch the output
+/// directly. For the full job report (timestamps, page progress, error
+/// detail), use `GET /jobs/{id}` instead.
+///
+/// The completed-PDF response is served through [`actix_files::NamedFile`]
+/// rather than loaded into memory and returned as one body: it streams the
+/// file in chunks, sets a strong `ETag` and `Last-Modified` from the file's
+/// own metadata, and — since the request is forwarded to it — transparently
+/// honors `Range`/`If-Range` with `206 Partial Content` and
+/// `Accept-Ranges: bytes`, so large multi-hundred-page outputs support
+/// resumable downloads.
+#[instrument(skip(req, services))]
+pub async fn get_job_status(
+    req: HttpRequest,
+    job_id: Path<String>,
+    services: web::Data<Arc<AppServices>>,
+) -> impl Responder {
+    let job_id = job_id.into_inner();
+    match services.job_scheduler.report(&job_id).await {
+        Some(report) if report.state == JobState::Completed => {
+            let output_filename = report.output_filename.unwrap_or_default();
+
+            // Ensures the file is actually staged on local disk (downloading
+            // it from the backing store first if it isn't) before we try to
+            // open it straight off disk below.
+            if services
+                .file_storage
+                .get_file(&output_filename)
+                .await
+                .is_none()
+            {
+                tracing::error!(
+                    "Job reported as done but output is missing. job_id={job_id} filename={output_filename}"
+                );
+                return HttpResponse::InternalServerError().body("Sanitised output is missing");
+            }
+
+            let output_path =
+                std::path::Path::new(services.file_storage.base_dir()).join(&output_filename);
+            match NamedFile::open_async(&output_path).await {
+                Ok(named_file) => named_file.into_response(&req),
+                Err(error) => {
+                    tracing::error!(
+                        "Failed to open sanitised output for streaming. filename={output_filename} error={error}"
+                    );
+                    HttpResponse::InternalServerError().body("Could not read sanitised output")
+                }
+            }
+        }
+        Some(report) => HttpResponse::Ok().json(report),
+        None => HttpResponse::NotFound().body("No such job"),
+    }
+}
+
+/// Returns the full [`crate::reports::JobReport`] for a submitted job as
+/// JSON, so a client can poll for state, page progress, and timestamps
+/// instead of relying solely on the success/failure callback.
+#[instrument(skip(services))]
+pub async fn get_job_report(
+    job_id: Path<String>,
+    services: web::Data<Arc<AppServices>>,
+) -> impl Responder {
+    match services.job_scheduler.report(&job_id.into_inner()).await {
+        Some(report) => HttpResponse::Ok().json(report),
+        None => HttpResponse::NotFound().body("No such job"),
+    }
+}
+
+/// Cancels a queued or in-progress job. A still-queued job is never picked up
+/// for processing; an in-progress job stops dispatching further chunks at its
+/// next opportunity, cleans up partial output, and sends neither callback.
+/// Returns 404 for a job that has already finished (or never existed) —
+/// there's nothing left to cancel.
+#[instrument(skip(services))]
+pub async fn cancel_job(
+    job_id: Path<String>,
+    services: web::Data<Arc<AppServices>>,
+) -> impl Responder {
+    let job_id = job_id.into_inner();
+    if services.job_scheduler.cancel(&job_id).await {
+        HttpResponse::Accepted().finish()
+    } else {
+        HttpResponse::NotFound().body("No such job")
+    }
+}
@@ -7,14 +7,19 @@ use actix_web::{
     web::{self, PayloadConfig},
 };
 use actix_web_opentelemetry::RequestTracing;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use futures::future;
 use tracing_actix_web::TracingLogger;
 
 use crate::{
-    app_settings::AppSettings,
-    routes::{health::health_check, sanitise::enqueue_pdf},
-    storage::FileStorage,
+    app_settings::{AppSettings, StorageConfig},
+    pdfium_pool::PdfiumPool,
+    routes::{
+        capabilities::{capabilities, ready},
+        health::health_check,
+        sanitise::{cancel_job, enqueue_pdf, get_job_report, get_job_status},
+    },
+    storage::{S3StorageConfig, StorageBackend},
     workers::job::SanitisePdfScheduler,
 };
 
@@ -28,7 +33,13 @@ pub struct Application {
 /// and other parts of our application
 pub struct AppServices {
     pub job_scheduler: Arc<SanitisePdfScheduler>,
-    pub file_storage: Arc<FileStorage<String>>,
+    pub file_storage: Arc<StorageBackend>,
+    /// Pool of pre-bound Pdfium instances. Actual page rendering happens in
+    /// per-chunk `procspawn` children (see `workers::job`), each binding its
+    /// own instance, since Pdfium isn't thread-safe; this pool instead backs
+    /// `/management/ready`, so readiness reflects a real, successful bind of
+    /// the Pdfium binary on this host rather than just the process starting.
+    pub pdfium_pool: Arc<PdfiumPool>,
 }
 
 impl Application {
@@ -59,7 +70,25 @@ impl Application {
 }
 
 // Allowing max of 50MB file size to be uploaded for now
-const MAX_PAYLOAD_SIZE: usize = 1024 * 1024 * 50;
+pub(crate) const MAX_PAYLOAD_SIZE: usize = 1024 * 1024 * 50;
+
+/// Builds the [`StorageBackend`] selected by `config`. Split out of `run`
+/// since `workers::job::SanitisePdfScheduler::build` constructs its own
+/// instance from the same settings for its internal scratch storage.
+async fn build_storage_backend(pdfs_dir: String, config: StorageConfig) -> Result<StorageBackend> {
+    match config {
+        StorageConfig::Local => Ok(StorageBackend::local(pdfs_dir)),
+        StorageConfig::S3 {
+            bucket,
+            region,
+            endpoint,
+        } => StorageBackend::s3(pdfs_dir, S3StorageConfig { bucket, region, endpoint })
+            .await
+            .context("Failed to initialise S3 storage backend"),
+        #[cfg(all(target_os = "linux", feature = "io_uring"))]
+        StorageConfig::Uring => Ok(StorageBackend::uring(pdfs_dir)),
+    }
+}
 
 async fn run(listener: TcpListener, settings: AppSettings) -> Result<(Server, Arc<AppServices>)> {
     let port = listener
@@ -80,15 +109,22 @@ async fn run(listener: TcpListener, settings: AppSettings) -> Result<(Server, Ar
         }
     };
 
-    let file_storage = FileStorage::new(settings.sanitisation.pdfs_dir.clone());
+    let file_storage = build_storage_backend(
+        settings.sanitisation.pdfs_dir.clone(),
+        settings.storage.clone(),
+    )
+    .await?;
     let file_storage = Arc::new(file_storage);
 
+    let pdfium_pool = Arc::new(PdfiumPool::new(settings.sanitisation.max_concurrent_jobs));
+
     let job_scheduler = SanitisePdfScheduler::build(settings).await?;
     let job_scheduler = Arc::new(job_scheduler);
 
     let services = AppServices {
         job_scheduler,
         file_storage,
+        pdfium_pool,
     };
 
     let arc_services = Arc::new(services);
@@ -100,7 +136,12 @@ async fn run(listener: TcpListener, settings: AppSettings) -> Result<(Server, Ar
             .wrap(TracingLogger::default())
             .wrap(RequestTracing::default())
             .route("/management/health", web::get().to(health_check))
+            .route("/management/capabilities", web::get().to(capabilities))
+            .route("/management/ready", web::get().to(ready))
             .route("/sanitise/pdf", web::post().to(enqueue_pdf))
+            .route("/sanitise/pdf/{job_id}", web::get().to(get_job_status))
+            .route("/jobs/{job_id}", web::get().to(get_job_report))
+            .route("/jobs/{job_id}", web::delete().to(cancel_job))
             .app_data(data_arc_services.clone())
             .app_data(PayloadConfig::new(MAX_PAYLOAD_SIZE))
     })
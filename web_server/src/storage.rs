@@ -2,7 +2,11 @@ use std::fs::{self, File};
 use std::io::{self, Write};
 use std::path::Path;
 
+use anyhow::Result;
+use aws_sdk_s3::Client as S3Client;
+
 /// A file storage service that provides functionality to store and retrieve files
+#[derive(Debug, Clone)]
 pub struct FileStorage {
     /// Base directory where files will be stored
     base_dir: String,
@@ -130,6 +134,305 @@ impl Default for FileStorage {
     }
 }
 
+/// Connection settings for [`S3Storage`], mirroring the shape of
+/// [`crate::app_settings::StorageConfig::S3`].
+#[derive(Debug, Clone)]
+pub struct S3StorageConfig {
+    pub bucket: String,
+    pub region: String,
+    /// Override for S3-compatible stores that aren't AWS itself (MinIO,
+    /// R2, etc). `None` uses the AWS SDK's regional default.
+    pub endpoint: Option<String>,
+}
+
+/// Durable, S3-backed counterpart to [`FileStorage`], selectable from
+/// [`crate::app_settings::StorageConfig`]. PDFium and `procspawn` only know
+/// how to read and write real files, so this still stages every file in a
+/// local directory exactly like [`FileStorage`] does — it just also
+/// write-through syncs to the bucket, so sanitised output and queued input
+/// survive a pod restart and are visible to every replica, not just the one
+/// that processed the job.
+#[derive(Debug, Clone)]
+pub struct S3Storage {
+    client: S3Client,
+    bucket: String,
+    cache: FileStorage,
+}
+
+impl S3Storage {
+    pub async fn new(base_dir: String, config: S3StorageConfig) -> Result<Self> {
+        let region = aws_config::Region::new(config.region);
+        let mut loader = aws_config::defaults(aws_config::BehaviorVersion::latest()).region(region);
+        if let Some(endpoint) = config.endpoint {
+            loader = loader.endpoint_url(endpoint);
+        }
+        let sdk_config = loader.load().await;
+        let client = S3Client::new(&sdk_config);
+
+        Ok(Self {
+            client,
+            bucket: config.bucket,
+            cache: FileStorage::new(base_dir),
+        })
+    }
+
+    fn key<P: AsRef<Path>>(&self, path: &P) -> String {
+        path.as_ref().to_string_lossy().into_owned()
+    }
+
+    pub async fn store_file<P: AsRef<Path>>(&self, path: &P, data: &[u8]) -> io::Result<()> {
+        self.cache.store_file(path, data)?;
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(self.key(path))
+            .body(data.to_vec().into())
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        Ok(())
+    }
+
+    /// Serves out of the local cache when possible; otherwise downloads the
+    /// object from the bucket into the cache first, so the returned `File`
+    /// always points at a real local path like [`FileStorage::get_file`]'s
+    /// does.
+    pub async fn get_file<P: AsRef<Path>>(&self, path: &P) -> Option<File> {
+        if self.cache.file_exists(path) {
+            return self.cache.get_file(path);
+        }
+
+        let output = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(self.key(path))
+            .send()
+            .await
+            .ok()?;
+        let body = output.body.collect().await.ok()?.into_bytes();
+        self.cache.store_file(path, &body).ok()?;
+        self.cache.get_file(path)
+    }
+
+    pub async fn file_exists<P: AsRef<Path>>(&self, path: &P) -> bool {
+        if self.cache.file_exists(path) {
+            return true;
+        }
+
+        self.client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(self.key(path))
+            .send()
+            .await
+            .is_ok()
+    }
+
+    pub async fn delete_file<P: AsRef<Path>>(&self, path: &P) -> io::Result<()> {
+        // Best-effort: the object is the source of truth, so a stale cache
+        // entry left behind by a failed local delete isn't worth failing
+        // the whole call over.
+        let _ = self.cache.delete_file(path);
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(self.key(path))
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        Ok(())
+    }
+
+    pub fn base_dir(&self) -> &str {
+        self.cache.base_dir()
+    }
+}
+
+/// Where sanitisation input/output files actually live, selected via
+/// [`crate::app_settings::StorageConfig`]. Mirrors the
+/// `JobBackendConfig`/`JobQueueStorage` split used for the job queue itself
+/// (see `workers::job`): the config enum is what `serde` deserialises, this
+/// runtime enum wraps one live instance per variant and dispatches to it by
+/// matching, rather than via a `dyn` trait object.
+#[derive(Debug, Clone)]
+pub enum StorageBackend {
+    Local(FileStorage),
+    S3(S3Storage),
+    /// `std::fs` + `File::create`/`sync_all` stalls a worker thread for the
+    /// full duration of each read/write — this submits through io_uring's
+    /// completion ring instead, for Linux hosts under high upload
+    /// concurrency. See [`uring::UringFileStorage`].
+    #[cfg(all(target_os = "linux", feature = "io_uring"))]
+    Uring(uring::UringFileStorage),
+}
+
+impl StorageBackend {
+    pub fn local(base_dir: String) -> Self {
+        StorageBackend::Local(FileStorage::new(base_dir))
+    }
+
+    pub async fn s3(base_dir: String, config: S3StorageConfig) -> Result<Self> {
+        Ok(StorageBackend::S3(S3Storage::new(base_dir, config).await?))
+    }
+
+    #[cfg(all(target_os = "linux", feature = "io_uring"))]
+    pub fn uring(base_dir: String) -> Self {
+        StorageBackend::Uring(uring::UringFileStorage::new(base_dir))
+    }
+
+    pub async fn store_file<P: AsRef<Path>>(&self, path: &P, data: &[u8]) -> io::Result<()> {
+        match self {
+            StorageBackend::Local(storage) => storage.store_file(path, data),
+            StorageBackend::S3(storage) => storage.store_file(path, data).await,
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            StorageBackend::Uring(storage) => storage.store_file(path, data).await,
+        }
+    }
+
+    pub async fn get_file<P: AsRef<Path>>(&self, path: &P) -> Option<File> {
+        match self {
+            StorageBackend::Local(storage) => storage.get_file(path),
+            StorageBackend::S3(storage) => storage.get_file(path).await,
+            // The io_uring path hands back bytes rather than a `File`, since
+            // there's no io_uring-backed way to produce a blocking-safe file
+            // object — `store_file` already wrote straight to `base_dir`
+            // like `FileStorage` does, so a caller that just wants a handle
+            // can open the same real path directly instead of re-reading it.
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            StorageBackend::Uring(storage) => {
+                let full_path = storage.full_path(path);
+                if full_path.exists() && full_path.is_file() {
+                    File::open(full_path).ok()
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    pub async fn file_exists<P: AsRef<Path>>(&self, path: &P) -> bool {
+        match self {
+            StorageBackend::Local(storage) => storage.file_exists(path),
+            StorageBackend::S3(storage) => storage.file_exists(path).await,
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            StorageBackend::Uring(storage) => storage.file_exists(path),
+        }
+    }
+
+    pub async fn delete_file<P: AsRef<Path>>(&self, path: &P) -> io::Result<()> {
+        match self {
+            StorageBackend::Local(storage) => storage.delete_file(path),
+            StorageBackend::S3(storage) => storage.delete_file(path).await,
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            StorageBackend::Uring(storage) => storage.delete_file(path),
+        }
+    }
+
+    /// Local directory backing this instance — for `Local` and `Uring`, the
+    /// directory files actually live in; for `S3`, the local staging cache
+    /// PDFium and `procspawn` read and write through.
+    pub fn base_dir(&self) -> &str {
+        match self {
+            StorageBackend::Local(storage) => storage.base_dir(),
+            StorageBackend::S3(storage) => storage.base_dir(),
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            StorageBackend::Uring(storage) => storage.base_dir(),
+        }
+    }
+}
+
+/// An io_uring-backed counterpart to [`FileStorage`], for Linux production
+/// deployments where avoiding blocking syscalls on actix's worker threads
+/// actually matters — sanitisation uploads/downloads can be multi-megabyte
+/// PDFs, and `std::fs` ties up a worker thread for the full duration of each
+/// read/write. Submits requests through io_uring submission queues instead.
+///
+/// Gated behind the `io_uring` feature and `cfg(target_os = "linux")`: it
+/// isn't available on macOS, and `tokio-uring` requires its own
+/// single-threaded-per-thread runtime (`tokio_uring::start(...)`), so it
+/// can't just be dropped into the regular tokio multi-threaded runtime.
+/// `FileStorage` remains the portable default; this exists to be selected
+/// explicitly on Linux once a caller is ready to run inside that runtime.
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+pub mod uring {
+    use std::io;
+    use std::path::{Path, PathBuf};
+
+    use tokio_uring::fs::File;
+
+    const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+    #[derive(Debug, Clone)]
+    pub struct UringFileStorage {
+        base_dir: String,
+    }
+
+    impl UringFileStorage {
+        pub fn new(base_dir: String) -> Self {
+            Self { base_dir }
+        }
+
+        pub(crate) fn full_path<P: AsRef<Path>>(&self, path: &P) -> PathBuf {
+            Path::new(&self.base_dir).join(path.as_ref())
+        }
+
+        /// Stores a file from a byte slice at the given path via io_uring writes.
+        pub async fn store_file<P: AsRef<Path>>(&self, path: &P, data: &[u8]) -> io::Result<()> {
+            let full_path = self.full_path(path);
+            if let Some(parent) = full_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+
+            let file = File::create(&full_path).await?;
+            let (res, _) = file.write_at(data.to_vec(), 0).await;
+            res?;
+            file.sync_all().await?;
+            file.close().await?;
+            Ok(())
+        }
+
+        /// Reads a file at the given path into memory via io_uring reads.
+        pub async fn get_file<P: AsRef<Path>>(&self, path: &P) -> io::Result<Vec<u8>> {
+            let full_path = self.full_path(path);
+            let metadata = std::fs::metadata(&full_path)?;
+            let file = File::open(&full_path).await?;
+
+            let mut contents = Vec::with_capacity(metadata.len() as usize);
+            let mut offset = 0u64;
+            loop {
+                let buf = Vec::with_capacity(READ_CHUNK_SIZE);
+                let (res, buf) = file.read_at(buf, offset).await;
+                let read = res?;
+                if read == 0 {
+                    break;
+                }
+                contents.extend_from_slice(&buf[..read]);
+                offset += read as u64;
+            }
+
+            file.close().await?;
+            Ok(contents)
+        }
+
+        /// Checks if a file exists at the given path
+        pub fn file_exists<P: AsRef<Path>>(&self, path: &P) -> bool {
+            let full_path = self.full_path(path);
+            full_path.exists() && full_path.is_file()
+        }
+
+        /// Deletes a file at the given path
+        pub fn delete_file<P: AsRef<Path>>(&self, path: &P) -> io::Result<()> {
+            std::fs::remove_file(self.full_path(path))
+        }
+
+        /// Gets the base directory of this storage instance
+        pub fn base_dir(&self) -> &str {
+            &self.base_dir
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
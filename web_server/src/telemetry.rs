@@ -19,6 +19,88 @@ use tracing_subscriber::{
     layer::SubscriberExt,
 };
 
+/// Service name used for traces/metrics/logs. `OTEL_SERVICE_NAME`, if set,
+/// takes priority over the `name` the caller passed in, per the OTel SDK's
+/// environment-variable configuration spec.
+fn resolve_service_name(name: &'static str) -> String {
+    env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| name.to_string())
+}
+
+/// Parses `OTEL_RESOURCE_ATTRIBUTES` (a comma-separated list of `key=value`
+/// pairs) into resource attributes, so deployment-specific metadata (pod
+/// name, region, ...) can be attached without recompiling.
+fn resource_attributes_from_env() -> Vec<KeyValue> {
+    env::var("OTEL_RESOURCE_ATTRIBUTES")
+        .ok()
+        .map(|raw| {
+            raw.split(',')
+                .filter_map(|pair| {
+                    let (key, value) = pair.split_once('=')?;
+                    Some(KeyValue::new(
+                        key.trim().to_string(),
+                        value.trim().to_string(),
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `OTEL_EXPORTER_OTLP_PROTOCOL` selects the OTLP wire format. We only speak
+/// HTTP transports (no gRPC exporter pulled in), so anything other than
+/// `http/protobuf` keeps the existing `http/json` default.
+fn otlp_protocol_from_env() -> opentelemetry_otlp::Protocol {
+    match env::var("OTEL_EXPORTER_OTLP_PROTOCOL").ok().as_deref() {
+        Some("http/protobuf") => opentelemetry_otlp::Protocol::HttpBinary,
+        _ => opentelemetry_otlp::Protocol::HttpJson,
+    }
+}
+
+/// General collector endpoint (`OTEL_EXPORTER_OTLP_ENDPOINT`), also the
+/// fallback for the traces endpoint.
+fn otlp_endpoint_from_env() -> Option<String> {
+    env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok()
+}
+
+/// Collector endpoint for traces: `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT` if
+/// set, otherwise the general `OTEL_EXPORTER_OTLP_ENDPOINT`, otherwise the
+/// exporter's own built-in default.
+fn otlp_traces_endpoint_from_env() -> Option<String> {
+    env::var("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT")
+        .ok()
+        .or_else(otlp_endpoint_from_env)
+}
+
+/// `OTEL_TRACES_SAMPLER` (+ `OTEL_TRACES_SAMPLER_ARG`) selects the trace
+/// sampler, defaulting to `AlwaysOn` (the prior hardcoded behavior) when
+/// unset or unrecognised.
+fn sampler_from_env() -> Sampler {
+    let ratio = env::var("OTEL_TRACES_SAMPLER_ARG")
+        .ok()
+        .and_then(|arg| arg.parse::<f64>().ok())
+        .unwrap_or(1.0);
+
+    match env::var("OTEL_TRACES_SAMPLER").ok().as_deref() {
+        Some("always_off") => Sampler::AlwaysOff,
+        Some("traceidratio") => Sampler::TraceIdRatioBased(ratio),
+        Some("parentbased_traceidratio") => {
+            Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(ratio)))
+        }
+        _ => Sampler::AlwaysOn,
+    }
+}
+
+/// `OTEL_METRIC_EXPORT_INTERVAL` (milliseconds) for the periodic metrics
+/// reader, defaulting to the prior hardcoded 2 seconds when unset or
+/// unparseable.
+fn metric_export_interval_from_env() -> Duration {
+    env::var("OTEL_METRIC_EXPORT_INTERVAL")
+        .ok()
+        .and_then(|interval| interval.parse::<u64>().ok())
+        .map(Duration::from_millis)
+        .unwrap_or_else(|| Duration::from_secs(2))
+}
+
 pub fn get_telemetry_subscriber<Sink>(
     name: &'static str,
     version: &'static str,
@@ -29,9 +111,10 @@ pub fn get_telemetry_subscriber<Sink>(
 where
     Sink: for<'a> MakeWriter<'a> + Send + Sync + 'static,
 {
+    let name = resolve_service_name(name);
     let env_filter =
         EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(env_filter));
-    let formatting_layer = BunyanFormattingLayer::new(name.into(), sink);
+    let formatting_layer = BunyanFormattingLayer::new(name.clone(), sink);
 
     // Optionally, add another transport layer so we get
     // log outputs on a file to inspect once Sake stops running.
@@ -45,10 +128,14 @@ where
         Err(_) => None,
     };
 
-    let span_exporter = SpanExporter::builder()
+    let mut span_exporter_builder = SpanExporter::builder()
         .with_http()
         .with_http_client(reqwest::Client::new())
-        .with_protocol(opentelemetry_otlp::Protocol::HttpJson)
+        .with_protocol(otlp_protocol_from_env());
+    if let Some(endpoint) = otlp_traces_endpoint_from_env() {
+        span_exporter_builder = span_exporter_builder.with_endpoint(endpoint);
+    }
+    let span_exporter = span_exporter_builder
         .build()
         .expect("Could not create SpanExporter");
 
@@ -58,11 +145,15 @@ where
     )
     .build();
 
-    // Automatically export metrics every 2 seconds so we can monitor CPU and RAM utilization.
-    let metrics_exporter = MetricExporter::builder()
+    // Exports metrics on OTEL_METRIC_EXPORT_INTERVAL (2s by default) so we can monitor CPU and RAM utilization.
+    let mut metrics_exporter_builder = MetricExporter::builder()
         .with_http()
         .with_http_client(reqwest::Client::new())
-        .with_protocol(opentelemetry_otlp::Protocol::HttpJson)
+        .with_protocol(otlp_protocol_from_env());
+    if let Some(endpoint) = otlp_endpoint_from_env() {
+        metrics_exporter_builder = metrics_exporter_builder.with_endpoint(endpoint);
+    }
+    let metrics_exporter = metrics_exporter_builder
         .build()
         .expect("could not create MetricExporter");
 
@@ -70,22 +161,22 @@ where
         metrics_exporter,
         runtime::Tokio,
     )
-    .with_interval(Duration::from_secs(2))
+    .with_interval(metric_export_interval_from_env())
     .build();
 
     let tracer = opentelemetry_sdk::trace::SdkTracerProvider::builder()
         .with_span_processor(batch_processor)
-        .with_sampler(Sampler::AlwaysOn)
+        .with_sampler(sampler_from_env())
         .with_id_generator(RandomIdGenerator::default())
         .with_max_events_per_span(64)
         .with_max_attributes_per_span(16)
-        .with_resource(get_resource(name, version, env_name))
+        .with_resource(get_resource(&name, version, env_name))
         .build()
         .tracer(name);
 
     let meter_provider = SdkMeterProvider::builder()
         .with_reader(periodic_reader)
-        .with_resource(get_resource(name, version, env_name))
+        .with_resource(get_resource(&name, version, env_name))
         .build();
 
     let opentelemetry_layer: OpenTelemetryLayer<Registry, _> = OpenTelemetryLayer::new(tracer);
@@ -110,6 +201,7 @@ fn get_resource(service_name: &str, version: &str, env_name: &str) -> Resource {
             env_name.to_owned(),
         ))
         .with_attribute(KeyValue::new("env", env_name.to_owned()))
+        .with_attributes(resource_attributes_from_env())
         .build()
 }
 
@@ -1,20 +1,46 @@
 use std::error::Error;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use actix_web::rt::signal;
 use anyhow::{Context, Result};
 use apalis::prelude::Error as JobError;
 use apalis::prelude::*;
+use apalis_sql::postgres::PostgresStorage;
 use procspawn::Pool;
-use sanitiser::pdf::sanitise::regenerate_pdf;
+use sanitiser::pdf::merge::merge_pdf_files;
+use sanitiser::pdf::sanitise::{RegenerationOptions, regenerate_pdf_with_options};
+use sanitiser::pdf::split::split_pdf_into_chunks;
+use sanitiser::pdf::structural::structural_clean;
 use serde::{Deserialize, Serialize};
 use serde_json;
-use tokio::sync::Mutex;
+use sqlx::PgPool;
+use tokio::sync::{Mutex, Semaphore};
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
 use tracing::instrument;
 
-use crate::app_settings::AppSettings;
-use crate::storage::FileStorage;
+use crate::app_settings::{AppSettings, JobBackendConfig, StorageConfig};
+use crate::cancellation::CancellationRegistry;
+use crate::dedupe::DedupeIndex;
+use crate::reports::{JobReport, JobReportStore};
+use crate::storage::{S3StorageConfig, StorageBackend};
+
+/// Sanitisation strategy selectable via the `mode` query param on
+/// `POST /sanitise/pdf`. `Raster` (the default) rasterises every page,
+/// destroying native text but guaranteeing no PDF object survives.
+/// `Structural` instead keeps the document's own content streams, fonts and
+/// images intact and only strips active-content constructs — see
+/// `sanitiser::pdf::structural::structural_clean`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SanitisationMode {
+    #[default]
+    Raster,
+    Structural,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SanitisePDFRequest {
@@ -22,6 +48,8 @@ pub struct SanitisePDFRequest {
     pub id: String,
     pub success_callback_url: String,
     pub failure_callback_url: String,
+    #[serde(default)]
+    pub mode: SanitisationMode,
 }
 
 impl SanitisePDFRequest {
@@ -30,12 +58,14 @@ impl SanitisePDFRequest {
         id: String,
         success_callback_url: String,
         failure_callback_url: String,
+        mode: SanitisationMode,
     ) -> Self {
         Self {
             filename,
             id,
             success_callback_url,
             failure_callback_url,
+            mode,
         }
     }
 }
@@ -53,56 +83,360 @@ impl std::fmt::Display for BackgroundJobError {
     }
 }
 
+/// The durable-storage side of the job queue: where queued/in-flight
+/// `SanitisePDFRequest`s actually live. `Memory` is the default for local
+/// development — enqueued jobs do not survive a restart. `Postgres` backs
+/// the queue with a table via `apalis-sql`, so a crash between HTTP accept
+/// and processing doesn't silently drop the file; apalis polls the table for
+/// pending rows itself, so pending jobs left over from before a restart are
+/// picked back up on their own, and jobs that were still running when the
+/// previous process died are re-enqueued once in `SanitisePdfScheduler::build`.
+enum JobQueueStorage {
+    Memory(MemoryStorage<SanitisePDFRequest>),
+    Postgres(PostgresStorage<SanitisePDFRequest>),
+}
+
+impl JobQueueStorage {
+    async fn enqueue(&mut self, job: SanitisePDFRequest) -> Result<()> {
+        match self {
+            JobQueueStorage::Memory(storage) => storage
+                .enqueue(job)
+                .await
+                .map_err(|_| anyhow::anyhow!("Failed to enqueue job in the in-memory backend")),
+            JobQueueStorage::Postgres(storage) => storage
+                .enqueue(job)
+                .await
+                .map_err(|_| anyhow::anyhow!("Failed to enqueue job in the Postgres backend")),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct SanitisePdfScheduler {
-    storage: Mutex<MemoryStorage<SanitisePDFRequest>>,
+    storage: Mutex<JobQueueStorage>,
     monitor: Mutex<Option<Monitor>>,
+    /// Number of jobs enqueued but not yet picked up by a worker. Exposed for
+    /// the capabilities/readiness endpoints. Only jobs enqueued by this
+    /// process are counted; jobs a `Postgres` queue carries over from before
+    /// a restart are not, and picking one up never takes the count below 0.
+    queue_depth: Arc<AtomicUsize>,
+    /// Job reports backing `GET /jobs/{id}` and `GET /sanitise/pdf/{job_id}`.
+    job_reports: JobReportStore,
+    /// Per-job cancellation tokens backing `DELETE /jobs/{id}`.
+    cancellations: CancellationRegistry,
+    /// Content-hash -> output-filename cache backing `try_serve_cached`.
+    dedupe: DedupeIndex,
+    /// Retry policy for delivering a cached success callback, mirroring the
+    /// same setting on [`WorkerData`].
+    max_retries: u32,
+    retry_base_delay_ms: u64,
+}
+
+impl std::fmt::Debug for JobQueueStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JobQueueStorage::Memory(_) => write!(f, "JobQueueStorage::Memory"),
+            JobQueueStorage::Postgres(_) => write!(f, "JobQueueStorage::Postgres"),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct WorkerData {
-    storage: FileStorage<String>,
+    storage: StorageBackend,
     pool: Pool,
+    /// Bounds how many sanitisation jobs may rasterise/merge at once,
+    /// independently of how many job futures apalis has in flight, so we
+    /// never oversubscribe memory on the host running pdfium.
+    job_permits: Arc<Semaphore>,
+    max_retries: u32,
+    retry_base_delay_ms: u64,
+    chunk_page_size: u16,
+    /// Upper bound on how many page chunks of one job may be regenerating at
+    /// once; each job gets its own set of slots (see `process_raster_job`).
+    /// Keeps a single large PDF from monopolising the `procspawn` pool.
+    chunk_parallelism: usize,
+    render_options: RegenerationOptions,
+    queue_depth: Arc<AtomicUsize>,
+    job_reports: JobReportStore,
+    cancellations: CancellationRegistry,
+    dedupe: DedupeIndex,
+}
+
+/// Sentinel `process_job` error used to signal that a job stopped because it
+/// was cancelled, not because regeneration or merging actually failed, so
+/// `sanitise_pdf` can skip the dead-letter/failure-callback path for it.
+/// Kept as a marker string rather than a new error enum to stay consistent
+/// with the rest of this pipeline's `Result<_, String>` convention.
+const CANCELLED_MARKER: &str = "__job_cancelled__";
+
+/// A small pseudo-random jitter (0..=max_ms), derived from the current time
+/// so we don't pull in an extra RNG dependency just for backoff spreading.
+fn small_jitter_ms(max_ms: u64) -> u64 {
+    if max_ms == 0 {
+        return 0;
+    }
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or(0);
+    nanos % (max_ms + 1)
+}
+
+/// Whether a failed attempt is worth retrying, or should fail fast.
+/// Empty input and malformed PDF structure are never going to succeed on a
+/// retry, whereas I/O and process-spawn hiccups usually are.
+fn is_retryable_error(error_msg: &str) -> bool {
+    !(error_msg.contains("EmptyInput")
+        || error_msg.contains("InvalidInput")
+        || error_msg.contains("no pages"))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Retry an async callback delivery attempt with the same exponential
+/// backoff + jitter policy used for chunk regeneration
+/// (`max_retries`/`retry_base_delay_ms`), so a transiently-down receiver
+/// doesn't permanently lose the job's result.
+async fn send_with_retries<F, Fut>(
+    max_retries: u32,
+    retry_base_delay_ms: u64,
+    mut attempt: F,
+) -> Result<(), anyhow::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<(), anyhow::Error>>,
+{
+    let max_attempts = max_retries.max(1);
+    let mut last_error = None;
+
+    for retry_attempt in 1..=max_attempts {
+        match attempt().await {
+            Ok(()) => return Ok(()),
+            Err(error) => {
+                if retry_attempt == max_attempts {
+                    last_error = Some(error);
+                    break;
+                }
+
+                let backoff_ms =
+                    (retry_base_delay_ms * 2u64.pow(retry_attempt - 1)).min(30_000);
+                let jitter_ms = backoff_ms / 10;
+                let sleep_ms = backoff_ms + small_jitter_ms(jitter_ms);
+                tracing::warn!(
+                    "Retrying callback delivery after failure. attempt={retry_attempt} next_delay_ms={sleep_ms} error={error}"
+                );
+                tokio::time::sleep(std::time::Duration::from_millis(sleep_ms)).await;
+                last_error = Some(error);
+            }
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| anyhow::anyhow!("Callback delivery failed")))
 }
 
 impl SanitisePdfScheduler {
     pub async fn build(settings: AppSettings) -> Result<Self> {
-        let storage: MemoryStorage<SanitisePDFRequest> = MemoryStorage::new();
-        let mutex_storage = Mutex::new(storage.clone());
+        let max_concurrent_jobs = settings.sanitisation.max_concurrent_jobs;
+        let max_retries = settings.sanitisation.max_retries;
+        let retry_base_delay_ms = settings.sanitisation.retry_base_delay_ms;
+        let chunk_parallelism = settings.sanitisation.chunk_parallelism;
+        let render_options = RegenerationOptions::new()
+            .dpi(settings.sanitisation.render_dpi)
+            .jpg_quality(settings.sanitisation.render_jpg_quality)
+            .page_batch(settings.sanitisation.render_page_batch);
+        let storage = match settings.storage.clone() {
+            StorageConfig::Local => StorageBackend::local(settings.sanitisation.pdfs_dir.clone()),
+            StorageConfig::S3 {
+                bucket,
+                region,
+                endpoint,
+            } => StorageBackend::s3(
+                settings.sanitisation.pdfs_dir.clone(),
+                S3StorageConfig { bucket, region, endpoint },
+            )
+            .await
+            .context("Failed to initialise S3 storage backend for the job worker")?,
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            StorageConfig::Uring => StorageBackend::uring(settings.sanitisation.pdfs_dir.clone()),
+        };
+        // Connected once up front (rather than inside the `job_backend` match
+        // below) so the same pool backs the durable queue, job reports and
+        // the dedupe index — all three need to survive a restart together,
+        // not just the queue.
+        let pg_pool = match &settings.job_backend {
+            JobBackendConfig::Memory => None,
+            JobBackendConfig::Postgres { database_url } => Some(
+                PgPool::connect(database_url)
+                    .await
+                    .context("Failed to connect to the Postgres job backend")?,
+            ),
+        };
 
-        let file_storage = FileStorage::new(settings.sanitisation.pdfs_dir);
         let pool = Pool::new(10).expect("Could not create pool of background processes");
+        let queue_depth = Arc::new(AtomicUsize::new(0));
+        let job_reports = match &pg_pool {
+            Some(pg_pool) => JobReportStore::new_postgres(pg_pool.clone())
+                .await
+                .context("Failed to set up the job_reports table")?,
+            None => JobReportStore::new(),
+        };
+        let cancellations = CancellationRegistry::new();
+        let dedupe = match &pg_pool {
+            Some(pg_pool) => DedupeIndex::new_postgres(pg_pool.clone())
+                .await
+                .context("Failed to set up the dedupe_index table")?,
+            None => DedupeIndex::new(),
+        };
         let worker_data = Arc::new(WorkerData {
-            storage: file_storage,
+            storage,
             pool,
+            job_permits: Arc::new(Semaphore::new(max_concurrent_jobs)),
+            max_retries,
+            retry_base_delay_ms,
+            chunk_page_size: settings.sanitisation.chunk_page_size,
+            chunk_parallelism,
+            render_options,
+            queue_depth: queue_depth.clone(),
+            job_reports: job_reports.clone(),
+            cancellations: cancellations.clone(),
+            dedupe: dedupe.clone(),
         });
 
-        let monitor = Monitor::new().register({
-            WorkerBuilder::new("pdf-regenerator")
-                .enable_tracing()
-                .data(worker_data)
-                .concurrency(10)
-                .backend(storage)
-                .build_fn(sanitise_pdf)
-        });
+        let (storage, monitor) = match settings.job_backend {
+            JobBackendConfig::Memory => {
+                let storage: MemoryStorage<SanitisePDFRequest> = MemoryStorage::new();
+                let monitor = Monitor::new().register({
+                    WorkerBuilder::new("pdf-regenerator")
+                        .enable_tracing()
+                        .data(worker_data)
+                        .concurrency(max_concurrent_jobs)
+                        .backend(storage.clone())
+                        .build_fn(sanitise_pdf)
+                });
+                (JobQueueStorage::Memory(storage), monitor)
+            }
+            JobBackendConfig::Postgres { .. } => {
+                let pool = pg_pool.expect("pg_pool is connected above for JobBackendConfig::Postgres");
+                PostgresStorage::setup(&pool)
+                    .await
+                    .context("Failed to run apalis-sql migrations against Postgres")?;
+                let storage: PostgresStorage<SanitisePDFRequest> = PostgresStorage::new(pool);
+
+                // Jobs that were still `Running` when the previous process
+                // died are stuck until someone re-queues them; apalis picks
+                // the rest (still `Pending`) back up on its own by polling.
+                if let Err(error) = storage.reenqueue_orphaned(max_concurrent_jobs as i32).await {
+                    tracing::warn!("Could not re-enqueue orphaned jobs on startup. error={error}");
+                }
+
+                let monitor = Monitor::new().register({
+                    WorkerBuilder::new("pdf-regenerator")
+                        .enable_tracing()
+                        .data(worker_data)
+                        .concurrency(max_concurrent_jobs)
+                        .backend(storage.clone())
+                        .build_fn(sanitise_pdf)
+                });
+                (JobQueueStorage::Postgres(storage), monitor)
+            }
+        };
 
         Ok(Self {
-            storage: mutex_storage,
+            storage: Mutex::new(storage),
             monitor: Mutex::new(Some(monitor)),
+            queue_depth,
+            job_reports,
+            cancellations,
+            dedupe,
+            max_retries,
+            retry_base_delay_ms,
         })
     }
 
     #[instrument(skip(self))]
     pub async fn enqueue(&self, job: SanitisePDFRequest) -> Result<()> {
+        let job_id = job.id.clone();
         let mut guard = self.storage.lock().await;
-        guard
-            .enqueue(job)
-            .await
-            .map_err(|_| anyhow::anyhow!("Failed to enqueue job"))?;
+        guard.enqueue(job).await?;
+        self.queue_depth.fetch_add(1, Ordering::Relaxed);
+        self.job_reports.mark_queued(&job_id).await;
+        self.cancellations.register(&job_id).await;
 
         Ok(())
     }
 
+    /// Look up the current report for a previously submitted job, if any.
+    pub async fn report(&self, job_id: &str) -> Option<JobReport> {
+        self.job_reports.get(job_id).await
+    }
+
+    /// Cancel a queued or in-progress job. A still-queued job is never picked
+    /// up for processing; an in-progress job stops dispatching further
+    /// chunks at its next opportunity, cleans up partial output, and sends
+    /// neither callback. Returns `false` if the job has already finished (or
+    /// never existed), in which case there's nothing left to cancel.
+    #[instrument(skip(self))]
+    pub async fn cancel(&self, job_id: &str) -> bool {
+        let cancelled = self.cancellations.cancel(job_id).await;
+        if cancelled {
+            self.job_reports.mark_cancelled(job_id).await;
+        }
+        cancelled
+    }
+
+    /// Check whether `content_hash` already has a cached sanitisation
+    /// output and, if so, deliver it straight to `request`'s success
+    /// callback instead of enqueuing a fresh job. Returns `true` if a cache
+    /// hit was served; `false` means the caller should enqueue normally
+    /// (either there's no cached output, or it's since been cleaned up from
+    /// disk).
+    #[instrument(skip(self, request, storage))]
+    pub async fn try_serve_cached(
+        &self,
+        content_hash: &str,
+        request: &SanitisePDFRequest,
+        storage: &StorageBackend,
+    ) -> bool {
+        let Some(output_filename) = self.dedupe.get(content_hash).await else {
+            return false;
+        };
+
+        if !storage.file_exists(&output_filename).await {
+            tracing::warn!(
+                "Cached output for content_hash={content_hash} is missing from storage, falling back to a fresh sanitisation"
+            );
+            return false;
+        }
+
+        tracing::info!(
+            "Serving cached sanitisation output for job id={} content_hash={content_hash}",
+            request.id
+        );
+
+        let output_path = Path::new(storage.base_dir()).join(&output_filename);
+        let client = reqwest::Client::new();
+        if let Err(e) = send_with_retries(self.max_retries, self.retry_base_delay_ms, || {
+            send_success_callback(&client, request, &output_path)
+        })
+        .await
+        {
+            tracing::error!("Failed to send cached success callback after retries. error={e}");
+        }
+
+        self.job_reports.mark_queued(&request.id).await;
+        self.job_reports
+            .mark_completed(&request.id, output_filename)
+            .await;
+
+        true
+    }
+
     pub async fn run_until_stopped(&self) -> std::io::Result<()> {
         let mut guard = self.monitor.lock().await;
         match guard.take() {
@@ -112,12 +446,376 @@ impl SanitisePdfScheduler {
             )),
         }
     }
+
+    /// Number of jobs enqueued but not yet picked up by a worker.
+    pub fn queue_depth(&self) -> usize {
+        self.queue_depth.load(Ordering::Relaxed)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct ProcData {
     original_file: String,
     output_file: String,
+    dpi: f32,
+    jpg_quality: f32,
+    page_batch: u16,
+}
+
+/// Run a single regeneration attempt against an arbitrary input/output pair
+/// in its own process via the procspawn pool.
+///
+/// The C++ PDF handling library we use isn't thread-safe, so the Rust
+/// wrapper (pdfium-render) puts a mutex behind the C++ bindings to avoid
+/// segfaulting. This means that running PDFium in multiple threads won't
+/// help us process multiple chunks at once.
+///
+/// PDFium works better with its own dedicated process. That way, it won't be
+/// able to access shared memory. By using procspawn, we are able to fork a
+/// child process and use PDFium isolated for each chunk, which is what
+/// actually lets `process_job` fan a single document out across the pool.
+fn regenerate_once(
+    pool: &Pool,
+    original_file: &Path,
+    output_file: &Path,
+    render_options: &RegenerationOptions,
+) -> Result<(), String> {
+    let args = ProcData {
+        original_file: original_file.to_str().unwrap().into(),
+        output_file: output_file.to_str().unwrap().into(),
+        dpi: render_options.dpi,
+        jpg_quality: render_options.jpg_quality,
+        page_batch: render_options.page_batch,
+    };
+
+    let proc_handle = procspawn::spawn!(in pool, (args) || {
+        let options = RegenerationOptions::new()
+            .dpi(args.dpi)
+            .jpg_quality(args.jpg_quality)
+            .page_batch(args.page_batch);
+
+        match regenerate_pdf_with_options(&args.original_file, &args.output_file, &options) {
+            Ok(()) => None,
+            Err(error) => Some(format!(
+                "Failed to regenerate file. filename={} error={}",
+                args.original_file, error
+            )),
+        }
+    });
+
+    match proc_handle.join() {
+        Ok(Some(error_msg)) => {
+            tracing::error!("Failed to sanitise PDF chunk in a background process. error={error_msg}");
+            Err(error_msg)
+        }
+        Ok(None) => {
+            tracing::info!("Background process done");
+            Ok(())
+        }
+        Err(error) => {
+            let error_msg = format!("Failed to spawn background process. error={error}");
+            tracing::error!("{}", error_msg);
+            Err(error_msg)
+        }
+    }
+}
+
+/// Retry `regenerate_once` with exponential backoff and jitter
+/// (`max_retries`/`retry_base_delay_ms`, the same policy `send_with_retries`
+/// uses for callbacks), scoped to a single chunk so one bad chunk doesn't
+/// force every other chunk to restart.
+fn regenerate_with_retries(
+    data: &WorkerData,
+    original_file: &Path,
+    output_file: &Path,
+) -> Result<(), String> {
+    let max_attempts = data.max_retries.max(1);
+    let mut last_error = String::new();
+
+    for attempt in 1..=max_attempts {
+        match regenerate_once(&data.pool, original_file, output_file, &data.render_options) {
+            Ok(()) => return Ok(()),
+            Err(error_msg) => {
+                last_error = error_msg.clone();
+                let retryable = is_retryable_error(&error_msg);
+                if !retryable || attempt == max_attempts {
+                    tracing::error!(
+                        "Giving up on chunk after {attempt} attempt(s). retryable={retryable} error={error_msg}"
+                    );
+                    return Err(error_msg);
+                }
+
+                let backoff_ms =
+                    (data.retry_base_delay_ms * 2u64.pow(attempt - 1)).min(30_000);
+                let jitter_ms = backoff_ms / 10;
+                let sleep_ms = backoff_ms + small_jitter_ms(jitter_ms);
+                tracing::warn!(
+                    "Retrying chunk after failure. attempt={attempt} next_delay_ms={sleep_ms} error={error_msg}"
+                );
+                std::thread::sleep(std::time::Duration::from_millis(sleep_ms));
+            }
+        }
+    }
+
+    Err(last_error)
+}
+
+/// Ensures `filename` is present in the local staging directory, downloading
+/// it through `storage` first if it isn't already there (e.g. a `Postgres`
+/// queue can hand this job to a different replica than the one that called
+/// `store_file` on upload) — `regenerate_pdf_with_options`/`structural_clean`/
+/// `split_pdf_into_chunks` only know how to read real files, not bytes, so
+/// the caller gets back the local path to open directly.
+async fn local_input_path(storage: &StorageBackend, filename: &str) -> Result<PathBuf, String> {
+    storage
+        .get_file(&filename)
+        .await
+        .ok_or_else(|| format!("Input file not found in storage. filename={filename}"))?;
+    Ok(Path::new(storage.base_dir()).join(filename))
+}
+
+/// Uploads the file at `local_path` through `storage.store_file`, keyed by
+/// its own filename, so an `S3` backend syncs the job's output to the
+/// bucket instead of leaving it only on the replica that produced it —
+/// otherwise `GET /sanitise/pdf/{job_id}` and the dedupe cache can only ever
+/// be served from that one replica.
+async fn persist_output(storage: &StorageBackend, local_path: &Path) -> Result<(), String> {
+    let filename = local_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| format!("Output path has no filename. path={}", local_path.display()))?;
+    let bytes = std::fs::read(local_path)
+        .map_err(|e| format!("Could not read output file for storage sync. error={e}"))?;
+    storage
+        .store_file(&filename, &bytes)
+        .await
+        .map_err(|e| format!("Could not persist output file to storage. error={e}"))
+}
+
+/// Delete the given files. Failure to remove any of them should not halt
+/// the pipeline — they're temporary intermediates, not the job's output.
+fn clean_up_temp_files(files: &[PathBuf]) {
+    for file in files {
+        if let Err(error) = std::fs::remove_file(file) {
+            tracing::warn!("Could not delete temp chunk file. filename={} error={error}", file.display());
+        }
+    }
+}
+
+/// Split `job`'s input into page chunks, sanitise each chunk in its own
+/// procspawn child (bounded by `data.chunk_parallelism` so one job can't
+/// monopolise the whole pool), then merge the sanitised chunks back together
+/// in their original order. This is what lets a large PDF fan out across
+/// the pool instead of serialising through a single child process.
+///
+/// `token` is checked before each chunk dispatch and again after all
+/// in-flight chunks settle, so a cancelled job stops growing but isn't force
+/// stopped mid-flight: we don't have a `procspawn` API we're confident
+/// enough in to kill a child actually rendering a chunk, so in-flight chunks
+/// are left to finish naturally and their output is discarded. In structural
+/// mode `token` is checked once the clean finishes, and its output is
+/// discarded if the job was cancelled meanwhile.
+async fn process_job(
+    data: &Arc<WorkerData>,
+    job: &SanitisePDFRequest,
+    token: &CancellationToken,
+) -> Result<PathBuf, String> {
+    match job.mode {
+        SanitisationMode::Raster => process_raster_job(data, job, token).await,
+        SanitisationMode::Structural => process_structural_job(data, job, token).await,
+    }
+}
+
+/// Structural mode needs none of the page-splitting/procspawn machinery
+/// `process_raster_job` uses: `structural_clean` is a single pure-Rust pass
+/// over the document's object graph, not pdfium rendering, so it runs
+/// in-process behind `spawn_blocking` and produces the output directly.
+async fn process_structural_job(
+    data: &Arc<WorkerData>,
+    job: &SanitisePDFRequest,
+    token: &CancellationToken,
+) -> Result<PathBuf, String> {
+    let original_file = local_input_path(&data.storage, &job.filename).await?;
+    let output_file = Path::new(data.storage.base_dir()).join(format!("processed_{}", &job.filename));
+
+    let clean_output = output_file.clone();
+    tokio::task::spawn_blocking(move || structural_clean(&original_file, &clean_output))
+        .await
+        .map_err(|e| format!("Structural clean task failed. error={e}"))?
+        .map_err(|e| format!("Failed to structurally clean PDF. error={e}"))?;
+
+    if token.is_cancelled() {
+        let _ = std::fs::remove_file(&output_file);
+        return Err(CANCELLED_MARKER.to_string());
+    }
+
+    persist_output(&data.storage, &output_file).await?;
+
+    Ok(output_file)
+}
+
+async fn process_raster_job(
+    data: &Arc<WorkerData>,
+    job: &SanitisePDFRequest,
+    token: &CancellationToken,
+) -> Result<PathBuf, String> {
+    let original_file = local_input_path(&data.storage, &job.filename).await?;
+    let chunk_page_size = data.chunk_page_size;
+
+    let split_data = data.clone();
+    let chunks = tokio::task::spawn_blocking(move || {
+        split_pdf_into_chunks(
+            &original_file,
+            chunk_page_size,
+            Path::new(split_data.storage.base_dir()),
+        )
+    })
+    .await
+    .map_err(|e| format!("Splitting task failed. error={e}"))??;
+
+    let chunk_permits = Arc::new(Semaphore::new(data.chunk_parallelism));
+    let mut joinset: JoinSet<(usize, Result<PathBuf, String>)> = JoinSet::new();
+
+    for (index, chunk_input) in chunks.iter().cloned().enumerate() {
+        if token.is_cancelled() {
+            tracing::info!(
+                "Job cancelled, stopping further chunk dispatch. job_id={} dispatched={index}",
+                job.id
+            );
+            break;
+        }
+
+        let permits = chunk_permits.clone();
+        let data = data.clone();
+        let chunk_output = Path::new(data.storage.base_dir()).join(format!(
+            "sanitised_{}",
+            chunk_input
+                .file_name()
+                .and_then(|f| f.to_str())
+                .unwrap_or("chunk.pdf")
+        ));
+
+        joinset.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let result = tokio::task::spawn_blocking(move || {
+                regenerate_with_retries(&data, &chunk_input, &chunk_output).map(|_| chunk_output.clone())
+            })
+            .await
+            .unwrap_or_else(|e| Err(format!("Chunk task failed. error={e}")));
+            (index, result)
+        });
+    }
+
+    // Gather results in completion order, but place them back by index so
+    // the final merge preserves original page order regardless of which
+    // chunk's child process finished first.
+    let mut outputs: Vec<Option<PathBuf>> = vec![None; chunks.len()];
+    let mut first_error: Option<String> = None;
+    while let Some(joined) = joinset.join_next().await {
+        match joined {
+            Ok((index, Ok(output))) => outputs[index] = Some(output),
+            Ok((index, Err(error_msg))) => {
+                tracing::error!("Chunk {index} failed to sanitise. error={error_msg}");
+                first_error.get_or_insert(error_msg);
+            }
+            Err(error) => {
+                tracing::error!("Chunk task panicked or was cancelled. error={error}");
+                first_error.get_or_insert(format!("Chunk task failed. error={error}"));
+            }
+        }
+    }
+
+    // The raw (pre-sanitisation) chunks are no longer needed either way.
+    clean_up_temp_files(&chunks);
+
+    let sanitised_chunks: Vec<PathBuf> = outputs.into_iter().flatten().collect();
+
+    if token.is_cancelled() {
+        clean_up_temp_files(&sanitised_chunks);
+        return Err(CANCELLED_MARKER.to_string());
+    }
+
+    if let Some(error_msg) = first_error {
+        clean_up_temp_files(&sanitised_chunks);
+        return Err(error_msg);
+    }
+
+    let output_file =
+        Path::new(data.storage.base_dir()).join(format!("processed_{}", &job.filename));
+
+    let merge_result = {
+        let sanitised_chunks = sanitised_chunks.clone();
+        let output_file = output_file.clone();
+        tokio::task::spawn_blocking(move || {
+            merge_pdf_files(&sanitised_chunks, &output_file, None, None)
+                .map_err(|e| format!("Failed to merge sanitised chunks. error={e}"))
+        })
+        .await
+        .map_err(|e| format!("Merge task failed. error={e}"))?
+    };
+
+    clean_up_temp_files(&sanitised_chunks);
+    merge_result?;
+
+    persist_output(&data.storage, &output_file).await?;
+
+    Ok(output_file)
+}
+
+/// A dead-lettered job's input file plus the error that finally gave up on
+/// it, persisted as a sidecar JSON file next to the moved input so it can be
+/// inspected (or the input replayed) without digging through logs.
+#[derive(Debug, Serialize)]
+struct DeadLetterRecord<'a> {
+    job_id: &'a str,
+    filename: &'a str,
+    error: &'a str,
+    failed_at: u64,
+}
+
+/// Move a permanently-failed job's input file into a `dead-letter`
+/// subdirectory under the sanitisation base dir, alongside a JSON record of
+/// the final error, so operators can inspect what went wrong (or replay the
+/// input) instead of the upload being silently lost.
+fn move_to_dead_letter(data: &WorkerData, job: &SanitisePDFRequest, error_msg: &str) {
+    let base_dir = Path::new(data.storage.base_dir());
+    let dead_letter_dir = base_dir.join("dead-letter");
+    if let Err(error) = std::fs::create_dir_all(&dead_letter_dir) {
+        tracing::error!("Could not create dead-letter directory. error={error}");
+        return;
+    }
+
+    let source = base_dir.join(&job.filename);
+    let destination = dead_letter_dir.join(&job.filename);
+    if let Err(error) = std::fs::rename(&source, &destination) {
+        tracing::error!(
+            "Could not move job to dead-letter directory. filename={} error={error}",
+            job.filename
+        );
+        return;
+    }
+
+    tracing::warn!(
+        "Job id={} moved to dead-letter. path={}",
+        job.id,
+        destination.display()
+    );
+
+    let record = DeadLetterRecord {
+        job_id: &job.id,
+        filename: &job.filename,
+        error: error_msg,
+        failed_at: now_secs(),
+    };
+    let record_path = dead_letter_dir.join(format!("{}.json", job.filename));
+    match serde_json::to_vec_pretty(&record) {
+        Ok(bytes) => {
+            if let Err(error) = std::fs::write(&record_path, bytes) {
+                tracing::error!("Could not persist dead-letter record. error={error}");
+            }
+        }
+        Err(error) => tracing::error!("Could not serialise dead-letter record. error={error}"),
+    }
 }
 
 #[instrument(skip(data))]
@@ -129,110 +827,118 @@ async fn sanitise_pdf(
     let inner_job = job.clone();
     let inner_data = data.clone();
 
-    let fut = tokio::task::spawn_blocking(move || {
-        tracing::info!("Processing PDF. filename={}", inner_job.filename);
-        let original_file = Path::new(inner_data.storage.base_dir()).join(&inner_job.filename);
-        let output_file = Path::new(inner_data.storage.base_dir())
-            .join(format!("processed_{}", &inner_job.filename));
+    // The job is no longer sitting in the queue once a worker has picked it
+    // up, regardless of how long it then waits on a processing permit.
+    let _ = inner_data
+        .queue_depth
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |depth| depth.checked_sub(1));
 
-        let args = ProcData {
-            original_file: original_file.to_str().unwrap().into(),
-            output_file: output_file.to_str().unwrap().into(),
-        };
+    let token = inner_data
+        .cancellations
+        .get(&inner_job.id)
+        .await
+        .unwrap_or_else(CancellationToken::new);
+
+    // A job cancelled while still queued never gets marked `Processing` —
+    // the cancel call already moved its report to `Cancelled`.
+    if token.is_cancelled() {
+        tracing::info!("Job id={} was cancelled before processing started", inner_job.id);
+        inner_data.cancellations.remove(&inner_job.id).await;
+        return Ok(());
+    }
 
-        // The C++ PDF handling library we use isn't thread-safe,
-        // So the Rust wrapper (pdfium-render) puts a mutex behind the
-        // C++ bindings to avoid segfaulting.
-        // This means that running PDFium in multiple threads won't help
-        // us to process multiple files at once.
-        //
-        // PDFium works better with its own deficated process.
-        // That way, it won't be able to access shared memory.
-        //
-        // By using procspawn, we are able to fork a child process
-        // and use PDFium isolated for each task.
-        //
-        // This is probably more costly, but we can improve this later
-        // with a process pool that can be reused across tasks.
-        let proc_handle = procspawn::spawn!(in inner_data.pool, (args) || {
-            match regenerate_pdf(&args.original_file, &args.output_file) {
-                Ok(()) => {
-                    tracing::info!("File regenerated successfully");
-                    None
-                }
-                Err(error) => Some(format!(
-                    "Failed to regenerate file. filename={} error={}",
-                    args.original_file, error
-                )),
-            }
-        });
+    inner_data.job_reports.mark_processing(&inner_job.id).await;
 
-        let result = match proc_handle.join() {
-            Ok(Some(error_msg)) => {
+    // Acquire a permit before doing any rasterisation/merge work so at most
+    // `max_concurrent_jobs` PDFs are processed at once. The permit is tied to
+    // this future's scope, so a cancelled or crashing job releases its slot
+    // automatically instead of leaking it.
+    let _permit = inner_data
+        .job_permits
+        .clone()
+        .acquire_owned()
+        .await
+        .map_err(|e| JobError::Failed(Arc::new(Box::new(std::io::Error::other(e)))))?;
+
+    tracing::info!("Processing PDF. filename={}", inner_job.filename);
+
+    let result = process_job(&inner_data, &inner_job, &token).await;
+
+    match result {
+        Ok(output_file) => {
+            if let Err(error) = inner_data.storage.delete_file(&inner_job.filename).await {
                 tracing::error!(
-                    "Failed to sanitise PDF in a background process. error={error_msg}"
+                    "Failed to clean-up original file. filename={} error={}",
+                    inner_job.filename,
+                    error
                 );
-                Err(error_msg)
-            }
-            Ok(None) => {
-                tracing::info!("Background process done");
-                Ok(output_file)
-            }
-            Err(error) => {
-                let error_msg = format!("Failed to spawn background process. error={error}");
-                tracing::error!("{}", error_msg);
-                Err(error_msg)
             }
-        };
-
-        if let Err(error) = inner_data.storage.delete_file(&inner_job.filename) {
-            tracing::error!(
-                "Failed to clean-up original file. filename={} error={}",
-                inner_job.filename,
-                error
-            );
-        }
-
-        result
-    });
 
-    match fut.await {
-        Ok(Ok(output_file)) => {
             // Success - send file to success callback
             tracing::info!("Sending success callback for job id={}", &job.id);
-            if let Err(e) = send_success_callback(&client, &job, &output_file).await {
-                tracing::error!("Failed to send success callback. error={e}");
+            if let Err(e) = send_with_retries(data.max_retries, data.retry_base_delay_ms, || {
+                send_success_callback(&client, &job, &output_file)
+            })
+            .await
+            {
+                tracing::error!("Failed to send success callback after retries. error={e}");
             }
 
-            if let Some(clean_up_file) = output_file.file_name()
-                && let Err(error) = data
-                    .storage
-                    .delete_file(&clean_up_file.to_str().unwrap().to_string())
-            {
-                tracing::error!(
-                    "Failed to clean-up final output file. filename={clean_up_file:#?} error={error}"
+            // Keep the output file in storage (rather than deleting it
+            // straight away) so `GET /sanitise/pdf/{job_id}` can still serve
+            // it to a caller that polls for status instead of receiving the
+            // success callback.
+            let output_filename = output_file
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default()
+                .to_string();
+            data.job_reports
+                .mark_completed(&job.id, output_filename.clone())
+                .await;
+            data.cancellations.remove(&job.id).await;
+
+            // The input filename is the content hash (see `enqueue_pdf`), so
+            // an identical future upload can be served straight from cache.
+            let content_hash = Path::new(&inner_job.filename)
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or_default()
+                .to_string();
+            data.dedupe.insert(content_hash, output_filename).await;
+
+            Ok(())
+        }
+        Err(error_msg) if error_msg == CANCELLED_MARKER => {
+            tracing::info!("Job id={} was cancelled during processing", job.id);
+
+            // No dead-letter entry and no callback — the caller asked for
+            // this job to stop, it isn't a failure worth reporting as one.
+            if let Err(error) = inner_data.storage.delete_file(&inner_job.filename).await {
+                tracing::warn!(
+                    "Failed to clean-up cancelled job's original file. filename={} error={}",
+                    inner_job.filename,
+                    error
                 );
             }
+            data.cancellations.remove(&job.id).await;
 
             Ok(())
         }
-        Ok(Err(error_msg)) => {
+        Err(error_msg) => {
+            move_to_dead_letter(&inner_data, &inner_job, &error_msg);
+
             // PDF processing failed - send error to failure callback
             tracing::info!("Sending failure callback for job id={}", job.id);
-            if let Err(e) = send_failure_callback(&client, &job, &error_msg).await {
-                tracing::error!("Failed to send failure callback. error={e}");
-            }
-            Err(JobError::Failed(Arc::new(Box::new(
-                BackgroundJobError::InvalidPDF,
-            ))))
-        }
-        Err(e) => {
-            // Task execution failed
-            let error_msg = format!("Processing task failed. error={e}");
-            tracing::error!("{}", error_msg);
-            if let Err(e) = send_failure_callback(&client, &job, &error_msg).await {
-                tracing::error!("Failed to send failure callback. error={e}");
+            if let Err(e) = send_with_retries(data.max_retries, data.retry_base_delay_ms, || {
+                send_failure_callback(&client, &job, &error_msg)
+            })
+            .await
+            {
+                tracing::error!("Failed to send failure callback after retries. error={e}");
             }
+            data.job_reports.mark_failed(&job.id, error_msg).await;
+            data.cancellations.remove(&job.id).await;
             Err(JobError::Failed(Arc::new(Box::new(
                 BackgroundJobError::InvalidPDF,
             ))))
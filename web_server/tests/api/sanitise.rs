@@ -30,10 +30,11 @@ async fn enqueue_pdf_success() {
         .expect("Failed to execute request");
 
     let status_code = response.status();
-    let response_body = response.text().await.expect("Failed to read response body");
+    let response_body: serde_json::Value =
+        response.json().await.expect("Failed to read response body");
 
-    assert!(status_code.is_success());
-    assert_eq!("PDF added to queue for processing", response_body);
+    assert_eq!(status_code, 202);
+    assert_eq!(response_body["job_id"], "test-123");
 }
 
 #[tokio::test]
@@ -90,10 +91,49 @@ async fn enqueue_pdf_with_test_pdf_file() {
         .expect("Failed to execute request");
 
     let status_code = response.status();
-    let response_body = response.text().await.expect("Failed to read response body");
+    let response_body: serde_json::Value =
+        response.json().await.expect("Failed to read response body");
 
-    assert!(status_code.is_success());
-    assert_eq!("PDF added to queue for processing", response_body);
+    assert_eq!(status_code, 202);
+    assert_eq!(response_body["job_id"], "test-real-pdf");
+}
+
+#[tokio::test]
+async fn job_status_reports_known_job_then_unknown_job_is_404() {
+    let app = spawn_app().await;
+
+    let test_pdf_content = create_minimal_pdf_content();
+    let temp_pdf = NamedTempFile::new().expect("Failed to create temporary PDF file");
+    fs::write(temp_pdf.path(), test_pdf_content).expect("Failed to write test PDF content");
+    let pdf_bytes = fs::read(temp_pdf.path()).expect("Failed to read test PDF file");
+
+    let client = reqwest::Client::new();
+    client
+        .post(format!("{}/sanitise/pdf", &app.address))
+        .query(&[
+            ("id", "test-status"),
+            ("success_callback_url", "http://example.com/success"),
+            ("failure_callback_url", "http://example.com/failure"),
+        ])
+        .header("Content-Type", "application/pdf")
+        .body(pdf_bytes)
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    let status_response = client
+        .get(format!("{}/sanitise/pdf/test-status", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert!(status_response.status().is_success());
+
+    let not_found_response = client
+        .get(format!("{}/sanitise/pdf/no-such-job", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(not_found_response.status(), 404);
 }
 
 /// Creates minimal PDF content for testing purposes